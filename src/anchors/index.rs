@@ -0,0 +1,81 @@
+//! Structured information about anchors, as written by the `anchors` target
+//! in JSON format and read by the rendering targets.
+
+use crate::preamble::*;
+use std::collections::HashMap;
+
+/// The kind of element an anchor points to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorKind {
+    /// The document itself.
+    Document,
+    /// A section heading.
+    Heading,
+    /// An explicit `Anchor` template.
+    Anchor,
+    /// A template with a `title`, like a theorem or definition.
+    Template,
+}
+
+/// An anchor with the information needed to describe it in a link.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorEntry {
+    /// The encoded anchor, as used for link resolution.
+    pub anchor: String,
+    pub kind: AnchorKind,
+    /// Identifier of the template for `Template` anchors, e.g. `theorem`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Plain text caption of the anchor target.
+    pub caption: String,
+    /// Captions of the headings enclosing the anchor, outermost first.
+    pub heading_path: Vec<String>,
+    /// Position of the anchor target in the source document.
+    pub position: Span,
+}
+
+/// The anchors available in an export.
+///
+/// Anchors read from a plain list have no further information attached.
+#[derive(Debug, Clone, Default)]
+pub struct AnchorIndex {
+    anchors: HashMap<String, Option<AnchorEntry>>,
+}
+
+impl AnchorIndex {
+    /// Build an index from the content of an anchor list. Each line is either
+    /// an anchor in plain text or an `AnchorEntry` in JSON, so per-article
+    /// lists of both formats can simply be concatenated.
+    pub fn parse(content: &str) -> io::Result<AnchorIndex> {
+        let mut anchors = HashMap::new();
+        for line in content
+            .split('\n')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+        {
+            if line.starts_with('{') {
+                let entry: AnchorEntry = serde_json::from_str(line)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                anchors.insert(entry.anchor.clone(), Some(entry));
+            } else {
+                anchors.insert(line.to_string(), None);
+            }
+        }
+        Ok(AnchorIndex { anchors })
+    }
+
+    /// Returns detailed information about an anchor, if present.
+    pub fn entry(&self, anchor: &str) -> Option<&AnchorEntry> {
+        self.anchors.get(anchor).and_then(|e| e.as_ref())
+    }
+
+    pub fn contains(&self, anchor: &str) -> bool {
+        self.anchors.contains_key(anchor)
+    }
+
+    /// Iterate over all available anchors.
+    pub fn anchors(&self) -> impl Iterator<Item = &String> {
+        self.anchors.keys()
+    }
+}
@@ -3,11 +3,24 @@
 //! The `anchors` target extracts all valid anchors (places which can be linked to)
 //! from an article. This allows to detect wether the target of a internal reference
 //! is available in the export or not.
+//!
+//! Optionally, a JSON index with kind, caption and heading path of each anchor
+//! is written, which the renderers use to generate link texts.
+//!
+//! Duplicate anchors are disambiguated like in MediaWiki and reported as warnings.
 
+use crate::diagnostics::{write_diagnostics, Diagnostic, Severity};
 use crate::preamble::*;
+use crate::title;
 use mfnf_template_spec::{parse_template, KnownTemplate};
 use structopt::StructOpt;
 
+mod index;
+mod registry;
+
+pub use self::index::{AnchorEntry, AnchorIndex, AnchorKind};
+pub use self::registry::{AnchorCollision, AnchorRegistry};
+
 const ANCHOR_CAPTION: &str = "Anker";
 
 #[derive(Debug, StructOpt)]
@@ -16,10 +29,29 @@ pub struct AnchorsArgs {
     doc_title: String,
 }
 
+/// Output format of the anchor list.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorFormat {
+    /// One anchor per line.
+    Plain,
+    /// One JSON `AnchorEntry` per line.
+    Json,
+}
+
+impl Default for AnchorFormat {
+    fn default() -> AnchorFormat {
+        AnchorFormat::Plain
+    }
+}
+
 /// Writes a list of valid anchors to the output.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
-pub struct AnchorsTarget {}
+pub struct AnchorsTarget {
+    /// Output format of the anchor list.
+    format: AnchorFormat,
+}
 
 impl<'a> Target<&'a AnchorsArgs, ()> for AnchorsTarget {
     fn target_type(&self) -> TargetType {
@@ -34,29 +66,77 @@ impl<'a> Target<&'a AnchorsArgs, ()> for AnchorsTarget {
         args: &'a AnchorsArgs,
         out: &mut io::Write,
     ) -> io::Result<()> {
-        let mut printer = AnchorPrinter::default();
-        printer.run(root, &args.doc_title, out)?;
+        let (anchors, registry) = collect_anchors(root, &args.doc_title);
+
+        let collisions: Vec<_> = registry
+            .collisions
+            .iter()
+            .map(|c| {
+                let message = format!(
+                    "duplicate anchor {:?}, first defined at {}:{}, renamed to make it unique.",
+                    &c.anchor, c.first.start.line, c.first.start.col
+                );
+                Diagnostic::new(
+                    Severity::Warning,
+                    "duplicate-anchor",
+                    message,
+                    &args.doc_title,
+                    &c.duplicate,
+                )
+            })
+            .collect();
+        write_diagnostics(&collisions, &mut io::stderr())?;
+
+        for entry in &anchors {
+            match self.format {
+                AnchorFormat::Plain => writeln!(out, "{}", &entry.anchor)?,
+                AnchorFormat::Json => writeln!(
+                    out,
+                    "{}",
+                    serde_json::to_string(entry).expect("could not serialize anchor entry!")
+                )?,
+            }
+        }
         writeln!(out)
     }
 }
 
-/// prints all possible link targets (anchors) within this article.
+/// Collect the unique anchors of a document in order of appearance.
+pub fn collect_anchors(root: &Element, doc_title: &str) -> (Vec<AnchorEntry>, AnchorRegistry) {
+    let mut printer = AnchorPrinter::default();
+    printer
+        .run(root, doc_title, &mut vec![])
+        .expect("unexpected IO error while collecting anchors!");
+    let registry = AnchorRegistry::new(&mut printer.anchors);
+    (printer.anchors, registry)
+}
+
+/// collects all possible link targets (anchors) within this article.
 #[derive(Default)]
 pub struct AnchorPrinter<'b> {
     pub path: Vec<&'b Element>,
+    /// anchors in order of appearance.
+    pub anchors: Vec<AnchorEntry>,
 }
 
 impl<'a, 'b: 'a> Traversion<'a, &'b str> for AnchorPrinter<'a> {
     path_methods!('a);
 
-    fn work(
-        &mut self,
-        root: &Element,
-        doc_title: &'b str,
-        out: &mut io::Write,
-    ) -> io::Result<bool> {
-        if let Some(anchor) = extract_anchor(root, doc_title) {
-            writeln!(out, "{}", anchor)?;
+    fn work(&mut self, root: &Element, doc_title: &'b str, _: &mut io::Write) -> io::Result<bool> {
+        // the current element is the last path element
+        let parents = &self.path[..self.path.len().saturating_sub(1)];
+        let heading_path = parents
+            .iter()
+            .filter_map(|e| match e {
+                Element::Heading(ref heading) => {
+                    Some(extract_plain_text(&heading.caption).trim().to_string())
+                }
+                _ => None,
+            })
+            .collect();
+
+        if let Some(entry) = extract_anchor_entry(root, doc_title, heading_path) {
+            self.anchors.push(entry);
         }
         Ok(true)
     }
@@ -65,12 +145,8 @@ impl<'a, 'b: 'a> Traversion<'a, &'b str> for AnchorPrinter<'a> {
 /// extract the anchor url from a template anchor
 pub fn extract_template_anchor(template: &KnownTemplate, doc_title: &str) -> Option<String> {
     fn format_url(name: &str, doc_title: &str) -> String {
-        format!(
-            "{}#{}:{}",
-            &mw_enc(doc_title),
-            &mw_enc(ANCHOR_CAPTION),
-            &mw_enc(name),
-        )
+        let fragment = format!("{}:{}", ANCHOR_CAPTION, name.trim());
+        title::anchor(doc_title, Some(&fragment))
     };
     match template {
         KnownTemplate::Anchor(ref anchor) => {
@@ -89,14 +165,51 @@ pub fn extract_template_anchor(template: &KnownTemplate, doc_title: &str) -> Opt
 
 /// extract the anchor url from a heading
 pub fn extract_heading_anchor(heading: &Heading, doc_title: &str) -> String {
-    let text = mw_enc(&extract_plain_text(&heading.caption));
-    let title = mw_enc(doc_title);
-    format!("{}#{}", &title, &text)
+    let text = extract_plain_text(&heading.caption);
+    title::anchor(doc_title, Some(&text))
 }
 
 /// extract the anchor url from a document
 pub fn extract_document_anchor(doc_title: &str) -> String {
-    mw_enc(doc_title)
+    title::anchor(doc_title, None)
+}
+
+/// extract the anchor and its description from an element if present.
+pub fn extract_anchor_entry(
+    root: &Element,
+    doc_title: &str,
+    heading_path: Vec<String>,
+) -> Option<AnchorEntry> {
+    let anchor = extract_anchor(root, doc_title)?;
+    let (kind, template, caption) = match root {
+        Element::Document(_) => (AnchorKind::Document, None, doc_title.to_string()),
+        Element::Heading(ref heading) => (
+            AnchorKind::Heading,
+            None,
+            extract_plain_text(&heading.caption),
+        ),
+        Element::Template(ref template) => match parse_template(template)? {
+            KnownTemplate::Anchor(ref anchor_template) => (
+                AnchorKind::Anchor,
+                None,
+                extract_plain_text(&anchor_template.ref1),
+            ),
+            ref known => (
+                AnchorKind::Template,
+                Some(known.identifier().to_lowercase()),
+                extract_plain_text(known.find("title")?.value),
+            ),
+        },
+        _ => return None,
+    };
+    Some(AnchorEntry {
+        anchor,
+        kind,
+        template,
+        caption: caption.trim().to_string(),
+        heading_path,
+        position: root.get_position().clone(),
+    })
 }
 
 /// extract the anchor url from an element if present.
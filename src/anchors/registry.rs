@@ -0,0 +1,75 @@
+//! Disambiguation of duplicate anchors within a document.
+
+use super::index::AnchorEntry;
+use crate::preamble::*;
+use std::collections::HashMap;
+
+/// Two elements of a document which would have the same anchor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorCollision {
+    /// The anchor before disambiguation.
+    pub anchor: String,
+    /// Position of the element keeping the original anchor.
+    pub first: Span,
+    /// Position of the element which got a suffixed anchor.
+    pub duplicate: Span,
+}
+
+/// The unique anchors of a document.
+///
+/// Like MediaWiki, the second occurrence of an anchor gets the suffix `_2`,
+/// the third `_3` and so on, skipping anchors which occurred before. Renderers
+/// look up the final anchor of an element by its original anchor and position,
+/// so elements they skip do not shift the anchors of the following ones.
+#[derive(Debug, Clone, Default)]
+pub struct AnchorRegistry {
+    /// original anchor -> positions and unique anchors of its occurrences
+    anchors: HashMap<String, Vec<(Span, String)>>,
+    /// all collisions found.
+    pub collisions: Vec<AnchorCollision>,
+}
+
+impl AnchorRegistry {
+    /// Make the anchors of `entries` (in document order) unique.
+    pub fn new(entries: &mut [AnchorEntry]) -> AnchorRegistry {
+        let mut registry = AnchorRegistry::default();
+        // unique anchor -> position of the element which got it
+        let mut used: HashMap<String, Span> = HashMap::new();
+
+        for entry in entries.iter_mut() {
+            let original = entry.anchor.clone();
+
+            if let Some(first) = used.get(&original).cloned() {
+                let mut counter = 2;
+                while used.contains_key(&format!("{}_{}", &original, counter)) {
+                    counter += 1;
+                }
+                entry.anchor = format!("{}_{}", &original, counter);
+
+                registry.collisions.push(AnchorCollision {
+                    anchor: original.clone(),
+                    first,
+                    duplicate: entry.position.clone(),
+                });
+            }
+            used.insert(entry.anchor.clone(), entry.position.clone());
+            registry
+                .anchors
+                .entry(original)
+                .or_default()
+                .push((entry.position.clone(), entry.anchor.clone()));
+        }
+        registry
+    }
+
+    /// Returns the unique anchor of the element at `position` with the
+    /// original anchor `anchor`. If a section is included more than once,
+    /// its elements share the anchor of the first occurrence.
+    pub fn resolve(&self, anchor: &str, position: &Span) -> String {
+        self.anchors
+            .get(anchor)
+            .and_then(|o| o.iter().find(|(p, _)| p == position))
+            .map(|(_, unique)| unique.clone())
+            .unwrap_or_else(|| anchor.to_string())
+    }
+}
@@ -0,0 +1,194 @@
+//! Accessibility checks for media, tables and formatting.
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::preamble::*;
+
+/// Number of figures of a document and how many of them lack a text alternative.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FigureSummary {
+    pub document: String,
+    pub figures: usize,
+    /// Figures without caption or alternative text.
+    pub inaccessible_figures: usize,
+}
+
+/// Collects accessibility problems of a document.
+pub struct AccessibilityChecker<'e> {
+    pub path: Vec<&'e Element>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub summary: FigureSummary,
+}
+
+impl<'e> AccessibilityChecker<'e> {
+    pub fn new(document: &str) -> AccessibilityChecker<'e> {
+        AccessibilityChecker {
+            path: vec![],
+            diagnostics: vec![],
+            summary: FigureSummary {
+                document: document.to_string(),
+                ..FigureSummary::default()
+            },
+        }
+    }
+
+    fn report(&mut self, severity: Severity, kind: &str, message: String, position: &Span) {
+        let document = &self.summary.document;
+        self.diagnostics
+            .push(Diagnostic::new(severity, kind, message, document, position));
+    }
+
+    fn check_image(&mut self, image: &InternalReference) {
+        self.summary.figures += 1;
+
+        let has_caption = !extract_plain_text(&image.caption).trim().is_empty();
+        let has_alt = image.options.iter().any(|o| {
+            let option = extract_plain_text(o);
+            let option = option.trim();
+            option.to_lowercase().starts_with("alt=") && !option[4..].trim().is_empty()
+        });
+        if has_caption || has_alt {
+            return;
+        }
+        self.summary.inaccessible_figures += 1;
+
+        let in_gallery = self.path.iter().any(|e| {
+            if let Element::Gallery(_) = e {
+                true
+            } else {
+                false
+            }
+        });
+        let message = format!(
+            "{} {:?} has neither a caption nor an `alt=` option.",
+            if in_gallery { "gallery image" } else { "image" },
+            extract_plain_text(&image.target).trim()
+        );
+        self.report(
+            Severity::Error,
+            "image-without-alt",
+            message,
+            &image.position,
+        );
+    }
+
+    fn check_table(&mut self, table: &Table) {
+        let has_header = table.rows.iter().any(|row| match row {
+            Element::TableRow(ref row) => row.cells.iter().any(|cell| match cell {
+                Element::TableCell(ref cell) => cell.header,
+                _ => false,
+            }),
+            _ => false,
+        });
+        if !has_header {
+            self.report(
+                Severity::Warning,
+                "table-without-header",
+                "table has no header cells.".into(),
+                &table.position,
+            );
+        }
+    }
+
+    fn check_color(&mut self, tag: &HtmlTag) {
+        let name = tag.name.trim().to_lowercase();
+        if name != "span" && name != "font" {
+            return;
+        }
+        let mut colored = false;
+        let mut emphasized = false;
+        for attribute in &tag.attributes {
+            match attribute.key.trim().to_lowercase().as_str() {
+                "color" => colored |= !attribute.value.trim().is_empty(),
+                "style" => {
+                    for (property, value) in style_declarations(&attribute.value) {
+                        match (property.as_str(), value.as_str()) {
+                            ("color", _) => colored = true,
+                            ("font-weight", "normal") | ("font-style", "normal") => (),
+                            ("font-weight", _) | ("font-style", _) => emphasized = true,
+                            _ => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        // the current element is the last path element
+        let parents = &self.path[..self.path.len().saturating_sub(1)];
+        emphasized |= parents.iter().any(|e| is_emphasis(e)) || contains_emphasis(&tag.content);
+        if colored && !emphasized {
+            self.report(
+                Severity::Warning,
+                "color-only-formatting",
+                "text is highlighted only by color.".into(),
+                &tag.position,
+            );
+        }
+    }
+}
+
+/// Lowercase property and value of each declaration of a `style` attribute.
+fn style_declarations(style: &str) -> Vec<(String, String)> {
+    style
+        .split(';')
+        .filter_map(|declaration| {
+            let mut parts = declaration.splitn(2, ':');
+            let property = parts.next()?.trim().to_lowercase();
+            let value = parts.next()?.trim().to_lowercase();
+            if property.is_empty() || value.is_empty() {
+                None
+            } else {
+                Some((property, value))
+            }
+        })
+        .collect()
+}
+
+/// Is this element bold or italic markup?
+fn is_emphasis(element: &Element) -> bool {
+    match element {
+        Element::Formatted(ref formatted) => match formatted.markup {
+            MarkupType::Bold | MarkupType::Italic => true,
+            _ => false,
+        },
+        Element::HtmlTag(ref tag) => match tag.name.trim().to_lowercase().as_str() {
+            "b" | "strong" | "i" | "em" => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Does this content contain bold or italic markup?
+fn contains_emphasis(content: &[Element]) -> bool {
+    content.iter().any(|element| {
+        is_emphasis(element)
+            || match element {
+                Element::Formatted(ref formatted) => contains_emphasis(&formatted.content),
+                Element::HtmlTag(ref tag) => contains_emphasis(&tag.content),
+                Element::Paragraph(ref paragraph) => contains_emphasis(&paragraph.content),
+                _ => false,
+            }
+    })
+}
+
+impl<'e, 's: 'e> Traversion<'e, &'s Settings> for AccessibilityChecker<'e> {
+    path_methods!('e);
+
+    fn work(
+        &mut self,
+        root: &'e Element,
+        settings: &'s Settings,
+        _: &mut io::Write,
+    ) -> io::Result<bool> {
+        match root {
+            Element::InternalReference(ref iref) if is_file(iref, settings) => {
+                self.check_image(iref)
+            }
+            Element::Table(ref table) => self.check_table(table),
+            Element::HtmlTag(ref tag) => self.check_color(tag),
+            _ => (),
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,197 @@
+//! Collection and offline checks of external links.
+
+use super::LinkAuditTarget;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::preamble::*;
+use std::collections::HashMap;
+
+/// URL schemes allowed in external links.
+const SCHEMES: &[&str] = &["http", "https", "ftp", "mailto"];
+
+/// An external link found in the document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalLink {
+    pub url: String,
+    /// Plain text caption of the link.
+    pub caption: String,
+    pub position: Span,
+}
+
+/// Result of a previous online check of an URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Was the URL reachable?
+    pub ok: bool,
+    /// HTTP status code, if a response was received.
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// Time of the check, as written by the checking job.
+    #[serde(default)]
+    pub checked: String,
+}
+
+/// Results of previous checks by URL.
+pub type LinkCache = HashMap<String, CacheEntry>;
+
+/// Collects all external links of a document.
+#[derive(Default)]
+pub struct LinkCollector<'e> {
+    pub path: Vec<&'e Element>,
+    pub links: Vec<ExternalLink>,
+}
+
+impl<'e> Traversion<'e, ()> for LinkCollector<'e> {
+    path_methods!('e);
+
+    fn work(&mut self, root: &'e Element, _: (), _: &mut io::Write) -> io::Result<bool> {
+        if let Element::ExternalReference(ref link) = root {
+            self.links.push(ExternalLink {
+                url: link.target.trim().to_string(),
+                caption: extract_plain_text(&link.caption).trim().to_string(),
+                position: link.position.clone(),
+            });
+        }
+        Ok(true)
+    }
+}
+
+/// Split an URL into scheme and host, or describe why this is not possible.
+/// Protocol-relative URLs (`//host/path`) have an empty scheme.
+pub fn split_url(url: &str) -> Result<(String, String), String> {
+    if url.chars().any(char::is_whitespace) {
+        return Err("URL contains whitespace".into());
+    }
+    let (scheme, rest) = if url.starts_with("//") {
+        // the page's scheme is used, which is http or https.
+        (String::new(), url)
+    } else {
+        match url.find(':') {
+            Some(pos) => (url[..pos].to_lowercase(), &url[pos + 1..]),
+            None => return Err("URL has no scheme".into()),
+        }
+    };
+    if !scheme.is_empty() && !SCHEMES.contains(&scheme.as_str()) {
+        return Err(format!("unknown URL scheme {:?}", &scheme));
+    }
+    if scheme == "mailto" {
+        return match rest.find('@') {
+            Some(pos) => Ok((scheme, rest[pos + 1..].to_lowercase())),
+            None => Err("mail address without \"@\"".into()),
+        };
+    }
+    if !rest.starts_with("//") {
+        return Err(format!("expected \"//\" after \"{}:\"", &scheme));
+    }
+    let authority = rest[2..]
+        .split(|c: char| c == '/' || c == '?' || c == '#')
+        .next();
+    let host = authority
+        .unwrap_or("")
+        .rsplit('@')
+        .next()
+        .unwrap_or("")
+        .split(':')
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+
+    let valid_host = !host.is_empty()
+        && !host.starts_with('.')
+        && !host.ends_with('.')
+        && host
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '.' || c == '-');
+    if !valid_host {
+        return Err(format!("invalid host {:?}", &host));
+    }
+    Ok((scheme, host))
+}
+
+/// Is `host` the domain or one of its subdomains?
+pub fn matches_domain(host: &str, domain: &str) -> bool {
+    let domain = domain.trim().trim_left_matches('.').to_lowercase();
+    host == domain || host.ends_with(&format!(".{}", &domain))
+}
+
+/// Check a link against the configuration and the cache.
+pub fn check_link(
+    link: &ExternalLink,
+    config: &LinkAuditTarget,
+    cache: Option<&LinkCache>,
+    document: &str,
+) -> Vec<Diagnostic> {
+    let mut result = vec![];
+    let mut report = |severity, kind: &str, message: String| {
+        result.push(Diagnostic::new(
+            severity,
+            kind,
+            message,
+            document,
+            &link.position,
+        ));
+    };
+
+    let (scheme, host) = match split_url(&link.url) {
+        Ok(parts) => parts,
+        Err(cause) => {
+            report(
+                Severity::Error,
+                "invalid-url",
+                format!("{:?} is not a valid URL: {}.", &link.url, cause),
+            );
+            return result;
+        }
+    };
+
+    if config.allowlist.iter().any(|d| matches_domain(&host, d)) {
+        return result;
+    }
+
+    if config.denylist.iter().any(|d| matches_domain(&host, d)) {
+        report(
+            Severity::Error,
+            "dead-domain",
+            format!("{:?} points to a domain known to be dead.", &link.url),
+        );
+        return result;
+    }
+
+    let https_expected = config.require_https
+        || config
+            .https_domains
+            .iter()
+            .any(|d| matches_domain(&host, d));
+    if scheme == "http" && https_expected {
+        report(
+            Severity::Warning,
+            "insecure-url",
+            format!("{:?} should use https.", &link.url),
+        );
+    }
+
+    if let Some(cache) = cache {
+        match cache.get(&link.url) {
+            Some(entry) if !entry.ok => {
+                let status = entry
+                    .status
+                    .map(|s| format!(" with status {}", s))
+                    .unwrap_or_default();
+                report(
+                    Severity::Error,
+                    "dead-link",
+                    format!(
+                        "{:?} was unreachable{} when checked {}.",
+                        &link.url, status, &entry.checked
+                    ),
+                );
+            }
+            Some(_) => (),
+            None => report(
+                Severity::Info,
+                "unchecked-link",
+                format!("{:?} is not in the link cache.", &link.url),
+            ),
+        }
+    }
+    result
+}
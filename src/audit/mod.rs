@@ -0,0 +1,191 @@
+//! Implementation of the audit targets.
+//!
+//! The `link-audit` target checks external links offline: URL syntax, `http`
+//! where `https` is expected and known-dead domains. Results of online checks
+//! can be provided as a cache file, which is refreshed by a separate job.
+//!
+//! The `accessibility` target reports images without text alternative,
+//! tables without header cells and color-only formatting, and counts
+//! the figures which are unusable with a screen reader.
+
+use crate::diagnostics::{has_errors, write_diagnostics, Diagnostic};
+use crate::inputs::record_input;
+use crate::preamble::*;
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+pub(crate) mod accessibility;
+mod links;
+
+use self::accessibility::AccessibilityChecker;
+pub use self::accessibility::FigureSummary;
+use self::links::{check_link, LinkCollector};
+pub use self::links::{split_url, CacheEntry, ExternalLink, LinkCache};
+
+#[derive(Debug, StructOpt)]
+pub struct LinkAuditArgs {
+    /// Title of the document beeing processed.
+    document_title: String,
+
+    /// Path to a JSON cache of previously checked URLs.
+    #[structopt(parse(from_os_str), short = "c", long = "cache")]
+    cache_path: Option<PathBuf>,
+
+    /// Write a JSON report of all links and problems to this file.
+    #[structopt(parse(from_os_str), short = "r", long = "report")]
+    report_path: Option<PathBuf>,
+}
+
+/// Checks the external links of a document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkAuditTarget {
+    /// Domains which are not checked.
+    allowlist: Vec<String>,
+    /// Domains known to be dead.
+    denylist: Vec<String>,
+    /// Domains which must be linked with `https`.
+    https_domains: Vec<String>,
+    /// Require `https` for all links.
+    require_https: bool,
+    /// Exit with an error if a link has errors.
+    fail_on_error: bool,
+}
+
+impl Default for LinkAuditTarget {
+    fn default() -> LinkAuditTarget {
+        LinkAuditTarget {
+            allowlist: vec![],
+            denylist: vec![],
+            https_domains: string_vec![
+                "wikipedia.org",
+                "wikibooks.org",
+                "wikimedia.org",
+                "wikidata.org",
+                "serlo.org"
+            ],
+            require_https: false,
+            fail_on_error: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct LinkReport<'a> {
+    links: &'a [ExternalLink],
+    diagnostics: &'a [Diagnostic],
+}
+
+impl<'a> Target<&'a LinkAuditArgs, ()> for LinkAuditTarget {
+    fn target_type(&self) -> TargetType {
+        TargetType::LinkAudit
+    }
+    fn export(
+        &self,
+        root: &Element,
+        _: (),
+        args: &'a LinkAuditArgs,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        let cache: Option<LinkCache> = match args.cache_path {
+            Some(ref path) => {
+                record_input(path);
+                let file = fs::File::open(path)?;
+                Some(serde_json::from_reader(&file).expect("error reading link cache:"))
+            }
+            None => None,
+        };
+
+        let mut collector = LinkCollector::default();
+        collector.run(root, (), out)?;
+
+        let diagnostics: Vec<_> = collector
+            .links
+            .iter()
+            .flat_map(|l| check_link(l, self, cache.as_ref(), &args.document_title))
+            .collect();
+
+        write_diagnostics(&diagnostics, out)?;
+        if let Some(ref path) = args.report_path {
+            let report = LinkReport {
+                links: &collector.links,
+                diagnostics: &diagnostics,
+            };
+            let file = fs::File::create(path)?;
+            serde_json::to_writer(&file, &report).expect("could not serialize link report!");
+        }
+        if self.fail_on_error && has_errors(&diagnostics) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the document contains invalid or dead links!",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct AccessibilityArgs {
+    /// Title of the document beeing processed.
+    document_title: String,
+
+    /// Write a JSON report with figure summary and problems to this file.
+    #[structopt(parse(from_os_str), short = "r", long = "report")]
+    report_path: Option<PathBuf>,
+}
+
+/// Checks media, tables and formatting for accessibility problems.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityTarget {
+    /// Exit with an error if a figure has no text alternative.
+    fail_on_error: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct AccessibilityReport<'a> {
+    summary: &'a FigureSummary,
+    diagnostics: &'a [Diagnostic],
+}
+
+impl<'a, 's> Target<&'a AccessibilityArgs, &'s Settings> for AccessibilityTarget {
+    fn target_type(&self) -> TargetType {
+        TargetType::Accessibility
+    }
+    fn export(
+        &self,
+        root: &Element,
+        settings: &'s Settings,
+        args: &'a AccessibilityArgs,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        let mut checker = AccessibilityChecker::new(&args.document_title);
+        checker.run(root, settings, out)?;
+
+        write_diagnostics(&checker.diagnostics, out)?;
+        let summary = &checker.summary;
+        writeln!(
+            out,
+            "{}: {} of {} figures unusable with a screen reader.",
+            &summary.document, summary.inaccessible_figures, summary.figures
+        )?;
+
+        if let Some(ref path) = args.report_path {
+            let report = AccessibilityReport {
+                summary,
+                diagnostics: &checker.diagnostics,
+            };
+            let file = fs::File::create(path)?;
+            serde_json::to_writer(&file, &report)
+                .expect("could not serialize accessibility report!");
+        }
+        if self.fail_on_error && has_errors(&checker.diagnostics) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the document contains inaccessible figures!",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//! Implements the `bibliography` target.
+//!
+//! The `bibliography` target merges the works cited in the articles of a book,
+//! as written by the `latex` target, to one BibLaTeX file. Works cited with the
+//! same data in several articles have the same key and are written only once.
+
+use crate::inputs::record_input;
+use crate::latex::BibEntry;
+use crate::preamble::*;
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct BibliographyArgs {
+    /// Bibliography files written by the `latex` target, one per article.
+    #[structopt(parse(from_os_str))]
+    entry_files: Vec<PathBuf>,
+}
+
+/// Merges the bibliographies of articles to a BibLaTeX file.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BibliographyTarget {
+    /// Sort entries by key instead of the order of their first citation.
+    sort_by_key: bool,
+}
+
+impl<'a> Target<&'a BibliographyArgs, ()> for BibliographyTarget {
+    fn target_type(&self) -> TargetType {
+        TargetType::Bibliography
+    }
+    fn export(
+        &self,
+        _: &Element,
+        _: (),
+        args: &'a BibliographyArgs,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        let mut merged: Vec<BibEntry> = vec![];
+        for path in &args.entry_files {
+            record_input(path);
+            let file = fs::File::open(path)?;
+            let entries: Vec<BibEntry> = serde_json::from_reader(&file).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("error reading bibliography {:?}: {}", path, err),
+                )
+            })?;
+
+            for entry in entries {
+                match merged.iter().find(|e| e.key == entry.key) {
+                    // keys contain a hash of all fields, so this is a hash collision.
+                    Some(existing) if *existing != entry => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("different works have the key `{}` ({:?})", &entry.key, path),
+                        ));
+                    }
+                    Some(_) => (),
+                    None => merged.push(entry),
+                }
+            }
+        }
+
+        if self.sort_by_key {
+            merged.sort_by(|a, b| a.key.cmp(&b.key));
+        }
+        for entry in &merged {
+            entry.write_bibtex(out)?;
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
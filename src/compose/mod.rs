@@ -5,6 +5,7 @@
 
 mod transformations;
 
+use crate::inputs::record_input;
 use crate::preamble::*;
 use crate::transformations::remove_exclusions;
 use mediawiki_parser::transformations::TResult;
@@ -29,6 +30,11 @@ pub struct ComposeArgs {
 #[serde(default)]
 pub struct ComposeTarget {}
 
+/// Prefix of the comment before an included section, followed by `article|section`.
+pub const INCLUSION_START: &str = "included from: ";
+/// Prefix of the comment after an included section, followed by `article|section`.
+pub const INCLUSION_END: &str = "end of inclusion: ";
+
 /// Prepare the article for rendering.
 pub fn compose(mut root: Element, section_path: &PathBuf, markers: &Markers) -> TResult {
     root = transformations::include_sections(root, section_path)?;
@@ -50,6 +56,7 @@ impl<'a> Target<&'a ComposeArgs, ()> for ComposeTarget {
         out: &mut io::Write,
     ) -> io::Result<()> {
         let markers = {
+            record_input(&args.marker_path);
             let file = fs::File::open(&args.marker_path)?;
             serde_json::from_reader(&file).expect("Error reading markers:")
         };
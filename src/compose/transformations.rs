@@ -1,3 +1,5 @@
+use super::{INCLUSION_END, INCLUSION_START};
+use crate::inputs::record_input;
 use crate::preamble::*;
 use mediawiki_parser::transformations::*;
 use mediawiki_parser::*;
@@ -47,6 +49,7 @@ pub fn include_sections_vec<'a>(
                     ),
                 });
 
+                record_input(&path);
                 let section_str = File::open(&path);
 
                 if section_str.is_err() {
@@ -65,7 +68,7 @@ pub fn include_sections_vec<'a>(
 
                 result.push(Element::Comment(Comment {
                     position: template.position.clone(),
-                    text: format!("included from: {}|{}", article, section_name),
+                    text: format!("{}{}|{}", INCLUSION_START, article, section_name),
                 }));
 
                 // recursively include sections
@@ -73,6 +76,10 @@ pub fn include_sections_vec<'a>(
                 section_tree =
                     include_sections_vec(&include_sections, &mut section_tree, section_path)?;
                 result.append(&mut section_tree);
+                result.push(Element::Comment(Comment {
+                    position: template.position.clone(),
+                    text: format!("{}{}|{}", INCLUSION_END, article, section_name),
+                }));
                 continue;
             }
         }
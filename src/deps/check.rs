@@ -0,0 +1,46 @@
+//! Checks that media dependencies are present and readable.
+
+use super::printers::MediaDependency;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::meta::MediaMeta;
+use std::fs;
+
+/// Check the source file and meta data of a media dependency.
+pub fn check_media(media: &MediaDependency, document: &str) -> Vec<Diagnostic> {
+    let mut result = vec![];
+    let error = |kind: &str, message: String| {
+        Diagnostic::new(Severity::Error, kind, message, document, &media.position)
+    };
+
+    if !media.source_path.is_file() {
+        result.push(error(
+            "missing-media",
+            format!(
+                "media file {:?} does not exist!",
+                &media.source_path.to_string_lossy()
+            ),
+        ));
+    }
+
+    match fs::File::open(&media.meta_path) {
+        Ok(file) => {
+            if serde_json::from_reader::<_, MediaMeta>(&file).is_err() {
+                result.push(error(
+                    "unreadable-meta",
+                    format!(
+                        "meta data {:?} could not be deserialized!",
+                        &media.meta_path.to_string_lossy()
+                    ),
+                ));
+            }
+        }
+        Err(_) => result.push(error(
+            "missing-meta",
+            format!(
+                "meta data {:?} could not be opened!",
+                &media.meta_path.to_string_lossy()
+            ),
+        )),
+    }
+    result
+}
@@ -0,0 +1,115 @@
+//! Output formats for dependency lists.
+
+use crate::preamble::*;
+use serde_json;
+
+/// Syntax in which dependencies are written.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepFormat {
+    /// `make` prerequisite lines.
+    Make,
+    /// Ninja depfile syntax (`depfile = ...` of a build statement).
+    Ninja,
+    /// A JSON manifest for other tools.
+    Json,
+}
+
+impl Default for DepFormat {
+    fn default() -> DepFormat {
+        DepFormat::Make
+    }
+}
+
+/// Dependency list of a target file, serialized as JSON manifest.
+#[derive(Debug, Clone, Serialize)]
+pub struct DepManifest<'a> {
+    /// The file these dependencies belong to.
+    pub target: &'a str,
+    /// Kind of the dependencies, e.g. `sections` or `media`.
+    pub kind: &'a str,
+    /// Comment line of the `make` format.
+    #[serde(skip)]
+    pub comment: String,
+    /// Paths of the dependencies.
+    pub dependencies: &'a [String],
+    /// Configuration and other files which influence the target.
+    pub inputs: &'a [String],
+}
+
+impl<'a> DepManifest<'a> {
+    /// All dependencies and inputs without duplicates.
+    pub fn all_dependencies(&self) -> Vec<&'a str> {
+        let mut result: Vec<&str> = vec![];
+        for dep in self.dependencies.iter().chain(self.inputs) {
+            if !result.contains(&dep.as_str()) {
+                result.push(dep);
+            }
+        }
+        result
+    }
+}
+
+/// Escape `$` by doubling it and the `special` characters with a backslash.
+/// Backslashes before an escaped character are doubled, others are kept.
+fn escape_path(path: &str, special: &[char]) -> String {
+    let mut res = String::new();
+    let mut backslashes = 0;
+    for c in path.chars() {
+        if special.contains(&c) {
+            res.push_str(&"\\".repeat(backslashes + 1));
+            res.push(c);
+        } else if c == '$' {
+            res.push_str("$$");
+        } else {
+            res.push(c);
+        }
+        backslashes = if c == '\\' { backslashes + 1 } else { 0 };
+    }
+    res
+}
+
+/// Escape a path for a ninja depfile.
+///
+/// Ninja reads spaces and `#` escaped with a backslash
+/// and `$` escaped by doubling it.
+pub fn escape_ninja(path: &str) -> String {
+    escape_path(path, &[' ', '#'])
+}
+
+/// Escape a path for a `make` rule. In addition to the escapes
+/// of ninja, `make` needs a backslash before `:`.
+pub fn escape_make(path: &str) -> String {
+    escape_path(path, &[' ', '#', ':'])
+}
+
+/// Write a dependency list of `manifest.target` in the given format.
+pub fn write_dependencies(
+    format: DepFormat,
+    manifest: &DepManifest,
+    out: &mut io::Write,
+) -> io::Result<()> {
+    match format {
+        DepFormat::Make => {
+            writeln!(out, "# {}", &manifest.comment)?;
+            write!(out, "{}: ", escape_make(manifest.target))?;
+            for dep in manifest.all_dependencies() {
+                write!(out, "\\\n\t{}", escape_make(dep))?;
+            }
+            writeln!(out)
+        }
+        // ninja depfiles do not support comments
+        DepFormat::Ninja => {
+            write!(out, "{}:", escape_ninja(manifest.target))?;
+            for dep in manifest.all_dependencies() {
+                write!(out, " {}", escape_ninja(dep))?;
+            }
+            writeln!(out)
+        }
+        DepFormat::Json => {
+            let json = serde_json::to_string(manifest)
+                .expect("could not serialize the dependency manifest!");
+            writeln!(out, "{}", json)
+        }
+    }
+}
@@ -1,17 +1,27 @@
 //! Implementation of the `deps` target.
 //!
 //! The `deps` target is used to export a list of article dependencies.
+//! Dependencies can be written as `make` rule, ninja depfile or JSON manifest.
+//! Files read during the run (configuration, markers, anchor lists) are listed as well.
+//! Files read by the runs rendering the article can be merged from their `--inputs-file` lists.
 //! It is applied to a syntax tree with only part of the export transformations applied.
 //! Transformations such as section inclusion or heading depth normalization are excluded,
 //! while others (e.g. tepmlate name translation, image prefix removal) are applied before
 //! this target is executed.
 
+use crate::diagnostics::{has_errors, write_diagnostics};
+use crate::inputs::{read_inputs, record_input, recorded_inputs};
 use crate::preamble::*;
+use std::collections::{HashSet, VecDeque};
 use std::fs;
 use std::path::PathBuf;
 
-mod printers;
+pub(crate) mod check;
+pub(crate) mod format;
+pub(crate) mod printers;
 
+pub use self::format::DepFormat;
+use self::format::{write_dependencies, DepManifest};
 use self::printers::*;
 use crate::transformations;
 use structopt::StructOpt;
@@ -27,12 +37,22 @@ pub struct SectionDepArgs {
     /// The target file to generate prerequisites for.
     #[structopt(short = "b", long = "base-file")]
     base_file: String,
+    /// Path to the list of available anchors used when rendering `base_file`.
+    #[structopt(parse(from_os_str), short = "a", long = "anchors")]
+    anchors_path: Option<PathBuf>,
+    /// Input lists written with `--inputs-file` by the runs rendering `base_file`.
+    #[structopt(parse(from_os_str), long = "inputs-from")]
+    inputs_from: Vec<PathBuf>,
 }
 
-/// Writes a list of included sections in `make` format.
+/// Writes a list of included sections, including those included
+/// indirectly through other sections.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
-pub struct SectionDepTarget {}
+pub struct SectionDepTarget {
+    /// Output format of the dependency list.
+    format: DepFormat,
+}
 
 impl<'a> Target<&'a SectionDepArgs, ()> for SectionDepTarget {
     fn target_type(&self) -> TargetType {
@@ -47,7 +67,12 @@ impl<'a> Target<&'a SectionDepArgs, ()> for SectionDepTarget {
         args: &'a SectionDepArgs,
         out: &mut io::Write,
     ) -> io::Result<()> {
+        if let Some(ref path) = args.anchors_path {
+            record_input(path);
+        }
+
         let markers = {
+            record_input(&args.marker_path);
             let file = fs::File::open(&args.marker_path)?;
             serde_json::from_reader(&file).expect("Error reading markers:")
         };
@@ -55,11 +80,82 @@ impl<'a> Target<&'a SectionDepArgs, ()> for SectionDepTarget {
         let root = transformations::remove_exclusions(root.clone(), &markers)
             .expect("error applying exclusions!");
 
-        write!(out, "{}: ", &args.base_file)?;
+        let sections = transitive_sections(&root, &args.section_path)?;
+        let mut inputs = input_dependencies();
+        for path in &args.inputs_from {
+            for input in read_inputs(path)? {
+                if !inputs.contains(&input) {
+                    inputs.push(input);
+                }
+            }
+        }
+
+        let manifest = DepManifest {
+            target: &args.base_file,
+            kind: "sections",
+            comment: format!("sections dependencies for {}", &args.base_file),
+            dependencies: &sections,
+            inputs: &inputs,
+        };
+        write_dependencies(self.format, &manifest, out)
+    }
+}
+
+/// Collect the section files included by `root` and, recursively,
+/// by the included sections themselves.
+///
+/// Sections which are not (yet) present in the section store are listed,
+/// but their inclusions cannot be followed. Sections which are present but
+/// cannot be read are reported.
+pub(crate) fn transitive_sections(
+    root: &Element,
+    section_path: &PathBuf,
+) -> io::Result<Vec<String>> {
+    let mut printer = InclusionPrinter::default();
+    printer.run(root, section_path, &mut vec![])?;
+
+    let mut result = vec![];
+    let mut visited = HashSet::new();
+    let mut queue: VecDeque<String> = printer.sections.into();
+
+    while let Some(path) = queue.pop_front() {
+        // also protects against inclusion cycles
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+        result.push(path.clone());
+
+        let section: Vec<Element> = match fs::File::open(&path) {
+            Ok(file) => match serde_json::from_reader(&file) {
+                Ok(section) => {
+                    record_input(&path);
+                    section
+                }
+                Err(err) => {
+                    eprintln!("could not parse included section {}: {}", &path, err);
+                    continue;
+                }
+            },
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                eprintln!("could not read included section {}: {}", &path, err);
+                continue;
+            }
+        };
+
         let mut printer = InclusionPrinter::default();
-        printer.run(&root, &args.section_path, out)?;
-        writeln!(out)
+        printer.run_vec(&section, section_path, &mut vec![])?;
+        queue.extend(printer.sections);
     }
+    Ok(result)
+}
+
+/// Configuration and other files read in this run.
+fn input_dependencies() -> Vec<String> {
+    recorded_inputs()
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
 }
 
 fn parse_target_type(input: &str) -> serde_json::Result<TargetType> {
@@ -76,12 +172,23 @@ pub struct MediaDepArgs {
     /// This determines media file extensions.
     #[structopt(parse(try_from_str = "parse_target_type"))]
     target_type: TargetType,
+
+    /// Write a JSON report of missing or unreadable media to this file.
+    #[structopt(parse(from_os_str), short = "r", long = "report")]
+    report_path: Option<PathBuf>,
 }
 
-/// Writes a list of included media files in `make` format.
+/// Writes a list of included media files.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
-pub struct MediaDepTarget {}
+pub struct MediaDepTarget {
+    /// Output format of the dependency list.
+    format: DepFormat,
+    /// Check that the source media and its meta data exist under `media_path`.
+    check_media: bool,
+    /// Abort with an error if media is missing or unreadable.
+    fail_on_missing: bool,
+}
 
 impl<'a, 's> Target<&'a MediaDepArgs, &'s Settings> for MediaDepTarget {
     fn target_type(&self) -> TargetType {
@@ -96,10 +203,38 @@ impl<'a, 's> Target<&'a MediaDepArgs, &'s Settings> for MediaDepTarget {
         args: &'a MediaDepArgs,
         out: &mut io::Write,
     ) -> io::Result<()> {
-        writeln!(out, "# dependencies for {}", &args.target_type)?;
-        write!(out, "{}: ", &args.base_file)?;
         let mut printer = FilesPrinter::new(args.target_type);
-        printer.run(&root, settings, out)?;
-        writeln!(out)
+        printer.run(&root, settings, &mut vec![])?;
+
+        if self.check_media || self.fail_on_missing {
+            let report: Vec<_> = printer
+                .files
+                .iter()
+                .flat_map(|f| check::check_media(f, &args.base_file))
+                .collect();
+
+            write_diagnostics(&report, &mut io::stderr())?;
+            if let Some(ref path) = args.report_path {
+                let file = fs::File::create(path)?;
+                serde_json::to_writer(&file, &report).expect("could not serialize media report!");
+            }
+            if self.fail_on_missing && has_errors(&report) {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "media files are missing or unreadable!",
+                ));
+            }
+        }
+
+        let dependencies = printer.dependencies();
+        let inputs = input_dependencies();
+        let manifest = DepManifest {
+            target: &args.base_file,
+            kind: "media",
+            comment: format!("dependencies for {}", &args.target_type),
+            dependencies: &dependencies,
+            inputs: &inputs,
+        };
+        write_dependencies(self.format, &manifest, out)
     }
 }
@@ -1,13 +1,15 @@
-//! Helpers which look for certain things in the input ast and print
-//! them to a given output in `make` dependency format.
+//! Helpers which look for certain things in the input ast and collect
+//! them as dependencies of the article.
 
 use crate::preamble::*;
 use std::path::PathBuf;
 
-/// Prints paths of the sections included in a document.
+/// Collects paths of the sections included in a document.
 #[derive(Default)]
 pub struct InclusionPrinter<'b> {
     pub path: Vec<&'b Element>,
+    /// paths of the included section files.
+    pub sections: Vec<String>,
 }
 
 impl<'a, 'b: 'a> Traversion<'a, &'b PathBuf> for InclusionPrinter<'a> {
@@ -17,7 +19,7 @@ impl<'a, 'b: 'a> Traversion<'a, &'b PathBuf> for InclusionPrinter<'a> {
         &mut self,
         root: &Element,
         section_path: &'b PathBuf,
-        out: &mut io::Write,
+        _: &mut io::Write,
     ) -> io::Result<bool> {
         if let Element::Template(ref template) = *root {
             let prefix = SECTION_INCLUSION_PREFIX;
@@ -34,18 +36,33 @@ impl<'a, 'b: 'a> Traversion<'a, &'b PathBuf> for InclusionPrinter<'a> {
                     .trim_matches('\'')
                     .to_string();
                 let path = get_section_path(&article, &section_name, section_path);
-                write!(out, "\\\n\t{}", &path)?;
+                self.sections.push(path);
             }
         };
         Ok(true)
     }
 }
 
-/// Print paths of file dependencies of an article.
+/// A media file referenced by an article.
+#[derive(Debug, Clone)]
+pub struct MediaDependency {
+    /// Path of the file as used by the target.
+    pub mapped_path: PathBuf,
+    /// Path of the original media file.
+    pub source_path: PathBuf,
+    /// Path of the media meta data.
+    pub meta_path: PathBuf,
+    /// Position of the first reference to this file.
+    pub position: Span,
+}
+
+/// Collects file dependencies of an article, without duplicates.
 pub struct FilesPrinter<'e> {
     pub path: Vec<&'e Element>,
     /// map of original to target file extension of a dependency.
     pub target_type: TargetType,
+    /// referenced media files in order of appearance.
+    pub files: Vec<MediaDependency>,
 }
 
 impl<'e, 's: 'e> Traversion<'e, &'s Settings> for FilesPrinter<'e> {
@@ -55,17 +72,24 @@ impl<'e, 's: 'e> Traversion<'e, &'s Settings> for FilesPrinter<'e> {
         &mut self,
         root: &Element,
         settings: &'s Settings,
-        out: &mut io::Write,
+        _: &mut io::Write,
     ) -> io::Result<bool> {
         if let Element::InternalReference(ref iref) = *root {
             if !is_file(iref, settings) {
                 return Ok(true);
             }
 
-            let file_path = build_media_path(&iref.target, settings);
-            let image_path = mapped_media_path(self.target_type, &iref.target, settings);
-            write!(out, "\\\n\t{}", &image_path.to_string_lossy())?;
-            write!(out, "\\\n\t{}.meta", &file_path.to_string_lossy())?;
+            let source_path = build_media_path(&iref.target, settings);
+            if self.files.iter().any(|f| f.source_path == source_path) {
+                return Ok(true);
+            }
+
+            self.files.push(MediaDependency {
+                mapped_path: mapped_media_path(self.target_type, &iref.target, settings),
+                meta_path: build_media_meta_path(&iref.target, settings),
+                source_path,
+                position: iref.position.clone(),
+            });
         };
         Ok(true)
     }
@@ -76,6 +100,17 @@ impl<'e> FilesPrinter<'e> {
         FilesPrinter {
             path: vec![],
             target_type,
+            files: vec![],
+        }
+    }
+
+    /// Paths of all media files and their meta data.
+    pub fn dependencies(&self) -> Vec<String> {
+        let mut result = vec![];
+        for file in &self.files {
+            result.push(file.mapped_path.to_string_lossy().to_string());
+            result.push(file.meta_path.to_string_lossy().to_string());
         }
+        result
     }
 }
@@ -0,0 +1,86 @@
+//! Positioned messages about problems found in a document.
+
+use colored::*;
+use mediawiki_parser::Span;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A problem found at a specific position of a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Identifier of the check which found this problem, e.g. `missing-meta`.
+    pub kind: String,
+    /// Human-readable description of the problem.
+    pub message: String,
+    /// Name of the document (or file) this problem was found in.
+    pub document: String,
+    /// Position of the problem in the document source.
+    pub position: Span,
+}
+
+impl Diagnostic {
+    pub fn new(
+        severity: Severity,
+        kind: &str,
+        message: String,
+        document: &str,
+        position: &Span,
+    ) -> Diagnostic {
+        Diagnostic {
+            severity,
+            kind: kind.to_string(),
+            message,
+            document: document.to_string(),
+            position: position.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "{}", "info".blue()),
+            Severity::Warning => write!(f, "{}", "warning".yellow()),
+            Severity::Error => write!(f, "{}", "error".red()),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}[{}]: {}",
+            &self.document,
+            self.position.start.line,
+            self.position.start.col,
+            self.severity,
+            &self.kind,
+            &self.message
+        )
+    }
+}
+
+/// Does the list contain any error?
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Write a list of diagnostics in human-readable form, one per line.
+pub fn write_diagnostics(diagnostics: &[Diagnostic], out: &mut io::Write) -> io::Result<()> {
+    for diagnostic in diagnostics {
+        writeln!(out, "{}", diagnostic)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,236 @@
+//! Footnotes with the semantics of MediaWiki's `<ref>` and `<references>` tags.
+//!
+//! A reference with a `name` attribute is defined once and reused by further
+//! `<ref name="..." />` tags. The definition may also come later in the document
+//! or inside a `<references>` tag. References are numbered per `group` in order
+//! of their first use. A `<references />` tag lists the references of its group
+//! cited so far, the following references of the group are numbered from 1 again.
+//!
+//! Reference names are scoped to the article they appear in, so sections
+//! included from different articles may use the same name for different notes.
+
+use crate::compose::{INCLUSION_END, INCLUSION_START};
+use crate::preamble::*;
+use std::collections::HashMap;
+
+/// Group of references without a `group` attribute.
+pub const DEFAULT_GROUP: &str = "";
+
+/// Article of the included section a named reference is used in,
+/// `None` outside of included sections.
+type Scope = Option<String>;
+
+/// Track the article of included sections by their marker comments.
+fn update_scopes(scopes: &mut Vec<String>, comment: &Comment) {
+    let text = comment.text.trim();
+    if text.starts_with(INCLUSION_START) {
+        let article = text[INCLUSION_START.len()..].split('|').next();
+        scopes.push(article.unwrap_or("").to_string());
+    } else if text.starts_with(INCLUSION_END) {
+        scopes.pop();
+    }
+}
+
+/// Get the value of an attribute of a html tag.
+pub fn tag_attribute<'a>(tag: &'a HtmlTag, key: &str) -> Option<&'a str> {
+    tag.attributes
+        .iter()
+        .find(|a| a.key.trim().to_lowercase() == key)
+        .map(|a| a.value.trim())
+        .filter(|v| !v.is_empty())
+}
+
+/// Group of a `<ref>` or `<references>` tag.
+pub fn tag_group(tag: &HtmlTag) -> &str {
+    tag_attribute(tag, "group").unwrap_or(DEFAULT_GROUP)
+}
+
+/// Content of a tag, if it is not empty.
+fn tag_content(tag: &HtmlTag) -> Option<&[Element]> {
+    let empty = tag.content.iter().all(|e| match e {
+        Element::Text(ref text) => text.text.trim().is_empty(),
+        _ => false,
+    });
+    if empty {
+        None
+    } else {
+        Some(&tag.content)
+    }
+}
+
+/// A reference in the list of its group.
+#[derive(Debug, Clone)]
+pub struct Note<'e> {
+    /// Unique id of the note in the document.
+    pub id: usize,
+    /// Number of the note in its list, starting at 1.
+    pub number: usize,
+    pub name: Option<String>,
+    /// Article the name of the note belongs to.
+    pub scope: Scope,
+    /// How often the note was cited.
+    pub uses: usize,
+    /// The content of the reference, if it is defined anywhere.
+    pub content: Option<&'e [Element]>,
+}
+
+/// A single use of a reference.
+#[derive(Debug, Clone)]
+pub struct Citation<'e> {
+    pub id: usize,
+    pub number: usize,
+    pub group: String,
+    pub name: Option<String>,
+    /// Number of this use of the note, starting at 1.
+    pub use_index: usize,
+    pub content: Option<&'e [Element]>,
+}
+
+impl<'e> Citation<'e> {
+    pub fn is_first_use(&self) -> bool {
+        self.use_index == 1
+    }
+
+    /// Label of the citation, like `3` or `note 3` for grouped references.
+    pub fn label(&self) -> String {
+        note_label(&self.group, self.number)
+    }
+}
+
+/// Label of a note, like `3` or `note 3` for grouped references.
+pub fn note_label(group: &str, number: usize) -> String {
+    if group == DEFAULT_GROUP {
+        number.to_string()
+    } else {
+        format!("{} {}", group, number)
+    }
+}
+
+/// Numbers the references of a document while it is rendered.
+#[derive(Debug, Default)]
+pub struct Footnotes<'e> {
+    /// (scope, group, name) -> content of the named reference.
+    definitions: HashMap<(Scope, String, String), &'e [Element]>,
+    /// Articles of the included sections currently rendered.
+    scopes: Vec<String>,
+    /// group -> notes cited since the last list of the group.
+    pending: HashMap<String, Vec<Note<'e>>>,
+    /// Number of notes created so far.
+    count: usize,
+}
+
+impl<'e> Footnotes<'e> {
+    /// Collect the definitions of named references in a document.
+    pub fn new(root: &'e Element) -> Footnotes<'e> {
+        let mut collector = DefinitionCollector::default();
+        collector
+            .run(root, (), &mut vec![])
+            .expect("unexpected IO error while collecting references!");
+        Footnotes {
+            definitions: collector.definitions,
+            ..Footnotes::default()
+        }
+    }
+
+    /// Keep track of included sections, must be called for every comment.
+    pub fn comment(&mut self, comment: &Comment) {
+        update_scopes(&mut self.scopes, comment);
+    }
+
+    /// Cite the reference of a `<ref>` tag.
+    pub fn cite(&mut self, tag: &'e HtmlTag) -> Citation<'e> {
+        let group = tag_group(tag).to_string();
+        let name = tag_attribute(tag, "name").map(|n| n.to_string());
+        let scope = self.scopes.last().cloned();
+        let notes = self.pending.entry(group.clone()).or_insert_with(Vec::new);
+
+        if let Some(ref name) = name {
+            let same_note = |n: &&mut Note| n.name.as_ref() == Some(name) && n.scope == scope;
+            if let Some(note) = notes.iter_mut().find(same_note) {
+                note.uses += 1;
+                return Citation {
+                    id: note.id,
+                    number: note.number,
+                    group,
+                    name: note.name.clone(),
+                    use_index: note.uses,
+                    content: note.content,
+                };
+            }
+        }
+
+        let definitions = &self.definitions;
+        let content = tag_content(tag).or_else(|| {
+            let name = name.as_ref()?;
+            let key = (scope.clone(), group.clone(), name.clone());
+            definitions.get(&key).cloned()
+        });
+        let note = Note {
+            id: self.count,
+            number: notes.len() + 1,
+            name,
+            scope,
+            uses: 1,
+            content,
+        };
+        self.count += 1;
+        let citation = Citation {
+            id: note.id,
+            number: note.number,
+            group,
+            name: note.name.clone(),
+            use_index: 1,
+            content,
+        };
+        notes.push(note);
+        citation
+    }
+
+    /// Take the notes of a group cited since its last list.
+    pub fn take_notes(&mut self, group: &str) -> Vec<Note<'e>> {
+        self.pending.remove(group).unwrap_or_default()
+    }
+
+    /// Groups with notes which were not listed yet, in sorted order.
+    pub fn pending_groups(&self) -> Vec<String> {
+        let mut groups: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, notes)| !notes.is_empty())
+            .map(|(group, _)| group.clone())
+            .collect();
+        groups.sort();
+        groups
+    }
+}
+
+/// Collects the first definition of every named reference.
+#[derive(Default)]
+struct DefinitionCollector<'e> {
+    path: Vec<&'e Element>,
+    scopes: Vec<String>,
+    definitions: HashMap<(Scope, String, String), &'e [Element]>,
+}
+
+impl<'e> Traversion<'e, ()> for DefinitionCollector<'e> {
+    path_methods!('e);
+
+    fn work(&mut self, root: &'e Element, _: (), _: &mut io::Write) -> io::Result<bool> {
+        match *root {
+            Element::Comment(ref comment) => update_scopes(&mut self.scopes, comment),
+            Element::HtmlTag(ref tag) if tag.name.trim().to_lowercase() == "ref" => {
+                if let (Some(name), Some(content)) = (tag_attribute(tag, "name"), tag_content(tag))
+                {
+                    let key = (
+                        self.scopes.last().cloned(),
+                        tag_group(tag).to_string(),
+                        name.to_string(),
+                    );
+                    self.definitions.entry(key).or_insert(content);
+                }
+            }
+            _ => (),
+        }
+        Ok(true)
+    }
+}
@@ -1,6 +1,8 @@
+use crate::anchors::{collect_anchors, AnchorIndex};
+use crate::footnotes::Footnotes;
+use crate::labels::LabelMap;
 use crate::preamble::*;
 use crate::transformations;
-use std::collections::HashSet;
 use std::io;
 mod renderer;
 
@@ -12,8 +14,8 @@ pub struct HTMLArgs {
     document_title: String,
 
     /// Path to a list of link targets (anchors) available in the export.
-    #[structopt(parse(try_from_str = "load_anchor_set"))]
-    available_anchors: HashSet<String>,
+    #[structopt(parse(try_from_str = "load_anchor_index"))]
+    available_anchors: AnchorIndex,
 }
 
 /// serialize to html
@@ -105,14 +107,24 @@ impl<'a, 's> Target<&'a HTMLArgs, &'s Settings> for HTMLTarget {
         args: &'a HTMLArgs,
         out: &mut io::Write,
     ) -> io::Result<()> {
-        let mut root = root.clone();
-        let mut renderer = renderer::HtmlRenderer::new(self, &settings, &args);
+        // anchors are made unique on the tree the `anchors` target sees
+        let (entries, anchors) = collect_anchors(root, &args.document_title);
 
+        let mut root = root.clone();
         if self.hoist_thumbnails {
             root =
                 transformations::hoist_thumbnails(root, ()).expect("could not hoist thumbnails!");
         }
+        let labels = LabelMap::new(
+            args.available_anchors
+                .anchors()
+                .chain(entries.iter().map(|e| &e.anchor)),
+        );
+        let footnotes = Footnotes::new(&root);
+        let mut renderer =
+            renderer::HtmlRenderer::new(self, &settings, &args, anchors, labels, footnotes);
 
-        renderer.run(&root, (), out)
+        renderer.run(&root, (), out)?;
+        renderer.remaining_references(out)
     }
 }
@@ -1,5 +1,6 @@
 use super::HtmlRenderer;
 use crate::preamble::*;
+use crate::title;
 
 impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
     pub fn internal_ref(
@@ -40,10 +41,41 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
             }
 
             return Ok(false);
-        } else {
-            self.write_error("internal links not implemented, yet!", out)?;
         }
 
+        self.internal_link(root, out)
+    }
+
+    /// links to anchors in the export or to other articles in the wiki.
+    fn internal_link(
+        &mut self,
+        root: &'e InternalReference,
+        out: &mut io::Write,
+    ) -> io::Result<bool> {
+        let target = extract_plain_text(&root.target);
+        let target = target.trim().trim_left_matches(':');
+        let doctitle = &self.args.document_title;
+
+        let href = match matching_anchor(target, doctitle, &self.args.available_anchors) {
+            Some(anchor) => format!("#{}", self.labels.label(&anchor)),
+            None => format!(
+                "{}{}",
+                &self.settings.article_url_base,
+                title::target_anchor(target, doctitle)
+            ),
+        };
+
+        write!(
+            out,
+            "<a class=\"internal-link\" href=\"{}\">",
+            Self::escape_html(&href)
+        )?;
+        if root.caption.is_empty() {
+            write!(out, "{}", Self::escape_html(target))?;
+        } else {
+            self.run_vec(&root.caption, (), out)?;
+        }
+        write!(out, "</a>")?;
         Ok(false)
     }
 
@@ -1,4 +1,7 @@
 use super::{HTMLArgs, HTMLTarget};
+use crate::anchors::AnchorRegistry;
+use crate::footnotes::Footnotes;
+use crate::labels::LabelMap;
 use crate::preamble::*;
 
 mod list;
@@ -13,6 +16,13 @@ pub struct HtmlRenderer<'e, 't, 's, 'a> {
 
     pub settings: &'s Settings,
     pub args: &'a HTMLArgs,
+
+    /// Unique anchors of the rendered document.
+    pub anchors: AnchorRegistry,
+    /// Element ids of all anchors in the export, shared with LaTeX labels.
+    pub labels: LabelMap,
+    /// Numbering of the references in the document.
+    pub footnotes: Footnotes<'e>,
 }
 
 impl<'e, 's: 'e, 't: 'e, 'a> Traversion<'e, ()> for HtmlRenderer<'e, 't, 's, 'a> {
@@ -49,12 +59,18 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
         target: &'t HTMLTarget,
         settings: &'s Settings,
         args: &'a HTMLArgs,
+        anchors: AnchorRegistry,
+        labels: LabelMap,
+        footnotes: Footnotes<'e>,
     ) -> HtmlRenderer<'e, 't, 's, 'a> {
         HtmlRenderer {
             path: vec![],
             html: target,
             settings,
             args,
+            anchors,
+            labels,
+            footnotes,
         }
     }
 
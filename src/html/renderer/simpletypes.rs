@@ -1,15 +1,22 @@
 //! HTMl renderer for all simple types like in the latex-renderer
 
 use super::HtmlRenderer;
+use crate::anchors::extract_heading_anchor;
+use crate::footnotes::{tag_group, Note};
 use crate::preamble::*;
 use mediawiki_parser::MarkupType;
 
 impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
     pub fn heading(&mut self, root: &'e Heading, out: &mut io::Write) -> io::Result<bool> {
+        let anchor = extract_heading_anchor(root, &self.args.document_title);
+        let anchor = self.anchors.resolve(&anchor, &root.position);
+        let anchor = self.labels.label(&anchor);
         write!(
             out,
-            "<h{} class=\"article-heading-{}\">",
-            &root.depth, &root.depth
+            "<h{} id=\"{}\" class=\"article-heading-{}\">",
+            &root.depth,
+            Self::escape_html(&anchor),
+            &root.depth
         )?;
         self.run_vec(&root.caption, (), out)?;
         writeln!(out, "</h{}>", &root.depth)?;
@@ -30,6 +37,7 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
     }
 
     pub fn comment(&mut self, root: &'e Comment, out: &mut io::Write) -> io::Result<bool> {
+        self.footnotes.comment(root);
         writeln!(out, "<!-- {} -->", Self::escape_html(&root.text))?;
         Ok(false)
     }
@@ -88,11 +96,21 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
                 self.run_vec(&root.content, (), out)?;
                 write!(out, "</dfn>")?;
             }
-            // TODO: proper footnotes
             "ref" => {
-                write!(out, "<sup>")?;
-                self.run_vec(&root.content, (), out)?;
-                write!(out, "</sup>")?;
+                let citation = self.footnotes.cite(root);
+                write!(
+                    out,
+                    "<sup class=\"reference\" id=\"cite-ref-{}-{}\">\
+                     <a href=\"#cite-note-{}\">[{}]</a></sup>",
+                    citation.id,
+                    citation.use_index,
+                    citation.id,
+                    Self::escape_html(&citation.label())
+                )?;
+            }
+            "references" => {
+                let notes = self.footnotes.take_notes(tag_group(root));
+                self.references(notes, out)?;
             }
             "section" => (),
             _ => {
@@ -107,6 +125,59 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
         Ok(false)
     }
 
+    /// Write a list of references with links back to their citations.
+    pub fn references(&mut self, notes: Vec<Note<'e>>, out: &mut io::Write) -> io::Result<()> {
+        if notes.is_empty() {
+            return Ok(());
+        }
+        writeln!(out, "<ol class=\"references\">")?;
+        for note in notes {
+            write!(
+                out,
+                "<li id=\"cite-note-{}\"><span class=\"backlinks\">",
+                note.id
+            )?;
+            if note.uses == 1 {
+                write!(out, "<a href=\"#cite-ref-{}-1\">↑</a>", note.id)?;
+            } else {
+                write!(out, "↑")?;
+                for use_index in 1..=note.uses {
+                    write!(
+                        out,
+                        " <a href=\"#cite-ref-{}-{}\">{}</a>",
+                        note.id,
+                        use_index,
+                        backlink_label(use_index)
+                    )?;
+                }
+            }
+            write!(out, "</span> <span class=\"reference-text\">")?;
+            match note.content {
+                Some(content) => {
+                    self.run_vec(content, (), out)?;
+                }
+                None => {
+                    let msg = format!(
+                        "reference `{}` is used, but never defined!",
+                        note.name.as_ref().map(|n| n.as_str()).unwrap_or("")
+                    );
+                    self.write_error(&msg, out)?;
+                }
+            }
+            writeln!(out, "</span></li>")?;
+        }
+        writeln!(out, "</ol>")
+    }
+
+    /// Write the references which were not placed by a `<references>` tag.
+    pub fn remaining_references(&mut self, out: &mut io::Write) -> io::Result<()> {
+        for group in self.footnotes.pending_groups() {
+            let notes = self.footnotes.take_notes(&group);
+            self.references(notes, out)?;
+        }
+        Ok(())
+    }
+
     pub fn formel(&mut self, root: &'e Formatted, out: &mut io::Write) -> io::Result<bool> {
         write!(out, "<span class=\"math\">")?;
         self.run_vec(&root.content, (), out)?;
@@ -114,3 +185,12 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
         Ok(false)
     }
 }
+
+/// Label of a link back to a citation, like MediaWiki: `a`, `b`, ...
+fn backlink_label(use_index: usize) -> String {
+    if use_index <= 26 {
+        ((b'a' + (use_index - 1) as u8) as char).to_string()
+    } else {
+        use_index.to_string()
+    }
+}
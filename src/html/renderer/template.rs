@@ -1,4 +1,5 @@
 use super::HtmlRenderer;
+use crate::anchors::extract_template_anchor;
 use crate::preamble::*;
 use mfnf_template_spec::*;
 use mwparser_utils::*;
@@ -33,6 +34,10 @@ macro_rules! div_wrapper {
 
 impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
     pub fn template(&mut self, root: &'e Template, out: &mut io::Write) -> io::Result<bool> {
+        // the back-of-book index only exists in print.
+        if is_index_template(root) {
+            return Ok(false);
+        }
         let parsed = if let Some(parsed) = parse_template(&root) {
             parsed
         } else {
@@ -72,7 +77,7 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
             | KnownTemplate::Exercise(_)
             | KnownTemplate::Hint(_) => {
                 let class = parsed.identifier().to_lowercase();
-                self.environment_template(&parsed, out, &class)?
+                self.environment_template(&parsed, &root.position, out, &class)?
             }
             KnownTemplate::Solution(solution) => self.solution(&solution, out)?,
             KnownTemplate::Smiley(smiley) => {
@@ -82,10 +87,7 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
                 write!(out, "{}", &unicode)?;
                 false
             }
-            KnownTemplate::Anchor(_) => {
-                self.write_error("TODO", out)?;
-                false
-            }
+            KnownTemplate::Anchor(_) => self.anchor(&parsed, &root.position, out)?,
             KnownTemplate::Mainarticle(_) => {
                 self.write_error("TODO", out)?;
                 false
@@ -97,7 +99,23 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
         };
         Ok(false)
     }
-    //important Todos: mainarticle: link? anchor: link?, literature, important
+    fn anchor(
+        &mut self,
+        template: &KnownTemplate<'e>,
+        position: &Span,
+        out: &mut io::Write,
+    ) -> io::Result<bool> {
+        if let Some(anchor) = extract_template_anchor(template, &self.args.document_title) {
+            let anchor = self.anchors.resolve(&anchor, position);
+            let id = self.labels.label(&anchor);
+            write!(out, "<span id=\"{}\"></span>", Self::escape_html(&id))?;
+        } else {
+            self.write_error("anchor export could not extract an anchor?", out)?;
+        }
+        Ok(false)
+    }
+
+    //important Todos: mainarticle: link? literature, important
     fn proof_by_cases(
         &mut self,
         cases: &ProofByCases<'e>,
@@ -241,10 +259,19 @@ impl<'e, 's: 'e, 't: 'e, 'a> HtmlRenderer<'e, 't, 's, 'a> {
     pub fn environment_template(
         &mut self,
         template: &KnownTemplate<'e>,
+        position: &Span,
         out: &mut io::Write,
         class: &str,
     ) -> io::Result<bool> {
-        write!(out, "<div class=\"{} environment\">", class)?;
+        match extract_template_anchor(template, &self.args.document_title) {
+            Some(anchor) => write!(
+                out,
+                "<div id=\"{}\" class=\"{} environment\">",
+                Self::escape_html(&self.labels.label(&self.anchors.resolve(&anchor, position))),
+                class
+            )?,
+            None => write!(out, "<div class=\"{} environment\">", class)?,
+        }
         write!(out, "<div class=\"icon icon-{}\">", class)?;
         let name = match template {
             KnownTemplate::Definition(_) => &self.html.strings.definition_caption,
@@ -0,0 +1,47 @@
+//! Tracks the files read while a target runs.
+//!
+//! Everything that influences the output (configuration, anchor lists, markers,
+//! sections, ...) is recorded here, so the `deps` targets can declare it
+//! as a build dependency.
+
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+thread_local! {
+    static INPUTS: RefCell<Vec<PathBuf>> = RefCell::new(vec![]);
+}
+
+/// Record that a file (or directory) is an input of the current run.
+pub fn record_input<P: AsRef<Path>>(path: P) {
+    let path = path.as_ref().to_path_buf();
+    INPUTS.with(|inputs| {
+        let mut inputs = inputs.borrow_mut();
+        if !inputs.contains(&path) {
+            inputs.push(path);
+        }
+    });
+}
+
+/// All inputs recorded so far, in order of first use.
+pub fn recorded_inputs() -> Vec<PathBuf> {
+    INPUTS.with(|inputs| inputs.borrow().clone())
+}
+
+/// Write the recorded inputs, one path per line (`--inputs-file`).
+pub fn write_inputs(out: &mut io::Write) -> io::Result<()> {
+    for input in recorded_inputs() {
+        writeln!(out, "{}", input.to_string_lossy())?;
+    }
+    Ok(())
+}
+
+/// Read an input list written by `write_inputs` (`--inputs-from`).
+pub fn read_inputs<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .filter(|input| !input.trim().is_empty())
+        .map(|input| input.to_string())
+        .collect())
+}
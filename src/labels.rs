@@ -0,0 +1,124 @@
+//! Readable identifiers for anchors, shared by LaTeX labels and HTML ids.
+//!
+//! An anchor like `Mathe_für_Nicht-Freaks:_Folgen#Anker:Satz` becomes the label
+//! `mathe-fuer-nicht-freaks:folgen:anker:satz`. Labels are derived from the
+//! complete set of anchors of an export, so every document agrees on them.
+
+use crate::title::{decode_dots, decode_percent, split_target};
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+/// Lowercase ASCII slug of a text. Colons are kept as separators.
+pub fn slug(input: &str) -> String {
+    let mut result = String::new();
+    for c in input.chars().flat_map(|c| c.to_lowercase()) {
+        let replacement = match c {
+            'a'...'z' | '0'...'9' | ':' => {
+                result.push(c);
+                continue;
+            }
+            'ä' => "ae",
+            'ö' => "oe",
+            'ü' => "ue",
+            'ß' => "ss",
+            _ => "-",
+        };
+        if replacement != "-" || !(result.is_empty() || result.ends_with('-')) {
+            result.push_str(replacement);
+        }
+    }
+    result
+        .split(':')
+        .map(|part| part.trim_matches('-'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Build the (not yet disambiguated) label of an anchor.
+pub fn anchor_slug(anchor: &str) -> String {
+    let (title, fragment) = split_target(anchor);
+    let mut result = slug(&decode_percent(title));
+    if let Some(fragment) = fragment {
+        result.push(':');
+        result.push_str(&slug(&decode_dots(fragment)));
+    }
+    if result.is_empty() {
+        result.push_str("anchor");
+    }
+    result
+}
+
+/// Short hash of a text, stable across runs and platforms (32 bit FNV-1a).
+pub fn short_hash(input: &str) -> String {
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in input.bytes() {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    format!("{:08x}", hash)
+}
+
+/// Maps anchors to unique, readable labels.
+#[derive(Debug, Clone, Default)]
+pub struct LabelMap {
+    /// anchor -> label
+    labels: HashMap<String, String>,
+    /// all labels assigned.
+    used: HashSet<String>,
+}
+
+impl LabelMap {
+    /// Assign labels to a set of anchors. If two anchors have the same slug,
+    /// both get a suffix derived from the anchor itself, so a label does not
+    /// depend on the order of the other anchors.
+    pub fn new<'a, I: Iterator<Item = &'a String>>(anchors: I) -> LabelMap {
+        let mut by_slug: HashMap<String, HashSet<&String>> = HashMap::new();
+        for anchor in anchors {
+            by_slug
+                .entry(anchor_slug(anchor))
+                .or_default()
+                .insert(anchor);
+        }
+
+        let mut map = LabelMap::default();
+        for (base, anchors) in by_slug {
+            for anchor in anchors.iter() {
+                let label = if anchors.len() == 1 {
+                    base.clone()
+                } else {
+                    format!("{}-{}", &base, short_hash(anchor))
+                };
+                map.used.insert(label.clone());
+                map.labels.insert(anchor.to_string(), label);
+            }
+        }
+        map
+    }
+
+    /// Returns the label of an anchor. Unknown anchors get their plain slug,
+    /// or a suffixed slug if the plain slug is the label of another anchor.
+    pub fn label(&self, anchor: &str) -> String {
+        if let Some(label) = self.labels.get(anchor) {
+            return label.clone();
+        }
+        let base = anchor_slug(anchor);
+        if self.used.contains(&base) {
+            format!("{}-{}", &base, short_hash(anchor))
+        } else {
+            base
+        }
+    }
+
+    /// Write the mapping of the given anchors as `label<TAB>anchor` lines.
+    pub fn write_map<'a, I: Iterator<Item = &'a String>>(
+        &self,
+        anchors: I,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        for anchor in anchors {
+            writeln!(out, "{}\t{}", self.label(anchor), anchor)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+//! Bibliography entries collected from `Literature` templates.
+//!
+//! Every work gets a key derived from its author, year and title and a hash of
+//! all its fields, so the same work cited in different articles gets the same
+//! key, while different works with similar titles do not. The entries of each
+//! article are written as JSON and merged to one BibLaTeX file by the
+//! `bibliography` target.
+
+use super::LatexRenderer;
+use crate::labels::{short_hash, slug};
+use crate::preamble::*;
+use mfnf_template_spec::Literature;
+
+/// Number of title words used in a key.
+const KEY_TITLE_WORDS: usize = 3;
+
+/// A work cited by a `Literature` template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BibEntry {
+    pub key: String,
+    pub author: Option<String>,
+    pub title: String,
+    pub publisher: Option<String>,
+    pub address: Option<String>,
+    pub year: Option<String>,
+    pub isbn: Option<String>,
+}
+
+/// Surname of the first author, for `Otto Forster` and `Forster, Otto`.
+fn first_surname(author: &str) -> &str {
+    let first = author.split(" and ").next().unwrap_or("").trim();
+    match first.find(',') {
+        Some(index) => &first[..index],
+        None => first.split_whitespace().last().unwrap_or(""),
+    }
+}
+
+/// Build the readable part of a key, like `forster:2016:analysis-1`.
+pub fn bib_key(author: Option<&str>, year: Option<&str>, title: &str) -> String {
+    let title = slug(title).replace(':', "-");
+    let title: Vec<&str> = title.split('-').take(KEY_TITLE_WORDS).collect();
+    let parts = vec![
+        slug(author.map(first_surname).unwrap_or("")).replace(':', "-"),
+        year.map(|y| y.chars().filter(|c| c.is_ascii_digit()).collect())
+            .unwrap_or_default(),
+        title.join("-"),
+    ];
+    parts
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+impl BibEntry {
+    pub fn from_literature(literature: &Literature) -> BibEntry {
+        let text = |e: Option<&[Element]>| {
+            e.map(|e| extract_plain_text(e).trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+        let author = text(literature.author);
+        let title = extract_plain_text(literature.title).trim().to_string();
+        let year = text(literature.year);
+        let mut entry = BibEntry {
+            key: bib_key(
+                author.as_ref().map(|a| a.as_str()),
+                year.as_ref().map(|y| y.as_str()),
+                &title,
+            ),
+            author,
+            title,
+            publisher: text(literature.publisher),
+            address: text(literature.address),
+            year,
+            isbn: text(literature.isbn),
+        };
+        entry.key = format!("{}:{}", &entry.key, entry.fields_hash());
+        entry
+    }
+
+    /// Hash of all fields, which tells different works with the same readable key apart.
+    pub fn fields_hash(&self) -> String {
+        let fields = [
+            self.author.as_ref(),
+            Some(&self.title),
+            self.publisher.as_ref(),
+            self.address.as_ref(),
+            self.year.as_ref(),
+            self.isbn.as_ref(),
+        ];
+        let text: Vec<&str> = fields
+            .iter()
+            .map(|f| f.map(|f| f.as_str()).unwrap_or(""))
+            .collect();
+        short_hash(&text.join("\u{1f}"))
+    }
+
+    /// Write the entry in BibLaTeX format.
+    pub fn write_bibtex(&self, out: &mut io::Write) -> io::Result<()> {
+        let title = Some(self.title.clone());
+        let fields = [
+            ("author", &self.author),
+            ("title", &title),
+            ("publisher", &self.publisher),
+            ("location", &self.address),
+            ("year", &self.year),
+            ("isbn", &self.isbn),
+        ];
+        writeln!(out, "@book{{{},", &self.key)?;
+        for (name, value) in fields.iter() {
+            if let Some(value) = value {
+                writeln!(
+                    out,
+                    "  {} = {{{}}},",
+                    name,
+                    LatexRenderer::escape_latex(value)
+                )?;
+            }
+        }
+        writeln!(out, "}}")
+    }
+}
@@ -0,0 +1,69 @@
+//! Entries of the back-of-book index.
+//!
+//! Index entries are generated for `<dfn>` tags, the titles of definitions and
+//! theorems, `Index` templates and configured terms. Every entry has a sort key
+//! built from its plain text, so formatted terms are sorted like plain words.
+
+/// Characters with special meaning in `\index`, which are quoted with `"`.
+const INDEX_SPECIAL_CHARS: &[char] = &['"', '!', '@', '|'];
+
+/// Sort key of an index term, sorting umlauts like their base letter.
+pub fn index_sort_key(term: &str) -> String {
+    let mut result = String::new();
+    for c in term.trim().chars().flat_map(|c| c.to_lowercase()) {
+        match c {
+            'ä' => result.push('a'),
+            'ö' => result.push('o'),
+            'ü' => result.push('u'),
+            'ß' => result.push_str("ss"),
+            c if c.is_alphanumeric() => result.push(c),
+            c if c.is_whitespace() || c == '-' => {
+                if !result.is_empty() && !result.ends_with(' ') {
+                    result.push(' ');
+                }
+            }
+            _ => (),
+        }
+    }
+    result.trim_right().to_string()
+}
+
+/// Quote characters which have a special meaning in index entries.
+/// Makeindex takes a `"` after a backslash literally, like in the accent `\"a`.
+/// Before other special characters, the backslash is quoted as well, like `"\"|`.
+pub fn escape_index(input: &str) -> String {
+    let mut result = String::new();
+    let mut escaped = false;
+    for c in input.chars() {
+        if INDEX_SPECIAL_CHARS.contains(&c) {
+            if !escaped {
+                result.push('"');
+            } else if c != '"' {
+                result.pop();
+                result.push_str("\"\\\"");
+            }
+        }
+        escaped = c == '\\' && !escaped;
+        result.push(c);
+    }
+    result
+}
+
+/// Byte offsets after each whole-word occurrence of the terms in a text,
+/// with the term found there, in order of appearance.
+pub fn find_terms<'t>(text: &str, terms: &'t [String]) -> Vec<(usize, &'t str)> {
+    let is_word_char = |c: Option<char>| c.map(|c| c.is_alphanumeric()).unwrap_or(false);
+    let mut found = vec![];
+    for term in terms.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        for (start, _) in text.match_indices(term) {
+            let end = start + term.len();
+            let before = text[..start].chars().next_back();
+            let after = text[end..].chars().next();
+            if !is_word_char(before) && !is_word_char(after) {
+                found.push((end, term));
+            }
+        }
+    }
+    found.sort();
+    found
+}
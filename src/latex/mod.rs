@@ -1,15 +1,40 @@
 //! Implementation of the `latex` target.
 //!
 //! This target renders the final syntax tree to a LaTeX document body.
-//! LaTeX boilerplate like preamble or document tags have to be added afterwards.
+//! LaTeX boilerplate like preamble or document tags have to be added afterwards,
+//! unless a document template is configured. The template is filled in at
+//! `<%placeholders%>` for the body, the title, the required packages, the
+//! captions typeset by the document class, the bibliography, the index and the
+//! page options exported by the `pdf` target.
+//!
+//! Works cited by `Literature` templates are rendered as citations. Their
+//! bibliography entries can be written to a file and merged for the whole book
+//! by the `bibliography` target.
+//!
+//! Optionally, a source map links ranges of output lines to the article and
+//! the position in the wiki source they were generated from.
 
+use crate::anchors::{collect_anchors, AnchorIndex};
+use crate::footnotes::Footnotes;
+use crate::inputs::record_input;
+use crate::labels::LabelMap;
 use crate::preamble::*;
 use crate::transformations;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
 
+mod bibliography;
+mod index;
 mod renderer;
+mod source_map;
+mod theme;
 
+pub use self::bibliography::{bib_key, BibEntry};
+pub use self::index::{escape_index, find_terms, index_sort_key};
 use self::renderer::LatexRenderer;
+pub use self::source_map::{extract_source_map, mark_origin, SourceMapEntry, SourceOrigin};
+pub use self::theme::{check_snippet, LatexTheme, SNIPPETS};
 
 use structopt::StructOpt;
 
@@ -19,10 +44,47 @@ pub struct LatexArgs {
     document_title: String,
 
     /// Path to a list of link targets (anchors) available in the export.
-    #[structopt(parse(try_from_str = "load_anchor_set"))]
-    available_anchors: HashSet<String>,
+    #[structopt(parse(try_from_str = "load_anchor_index"))]
+    available_anchors: AnchorIndex,
+
+    /// Write the labels of this document and their anchors to this file.
+    #[structopt(parse(from_os_str), short = "l", long = "label-map")]
+    label_map: Option<PathBuf>,
+
+    /// Page options as exported by the `pdf` target, used in the document template.
+    #[structopt(parse(from_os_str), short = "p", long = "pdf-options")]
+    pdf_options: Option<PathBuf>,
+
+    /// Write a JSON map from output lines to their wiki source positions to this file.
+    #[structopt(parse(from_os_str), short = "m", long = "source-map")]
+    source_map: Option<PathBuf>,
+
+    /// Write the works cited in this document to this file, for the `bibliography` target.
+    #[structopt(parse(from_os_str), short = "b", long = "bibliography")]
+    bibliography: Option<PathBuf>,
 }
 
+/// Packages needed by the LaTeX snippets of the renderer, as (options, package).
+const REQUIRED_PACKAGES: &[(&str, &str)] = &[
+    ("", "amsmath"),
+    ("", "amssymb"),
+    ("export", "adjustbox"),
+    ("normalem", "ulem"),
+    ("", "soul"),
+    ("", "csquotes"),
+    ("", "caption"),
+    ("", "longtable"),
+    ("", "tabu"),
+    ("", "booktabs"),
+    ("", "comment"),
+    ("", "footmisc"),
+    ("", "imakeidx"),
+    ("", "hyperref"),
+];
+
+/// Options of the biblatex package, `{}` is replaced by the citation style.
+const BIBLATEX_OPTIONS: &str = "backend=biber, style={}";
+
 /// Data for LaTeX export.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -60,6 +122,30 @@ pub struct LatexTarget {
     /// Environments which are not affected by the `environment_numbers` option.
     /// Entries always in their plain (whithout `*`) form.
     environment_numbers_exceptions: Vec<String>,
+
+    /// Configures location-dependent strings.
+    strings: Box<LatexStrings>,
+
+    /// LaTeX snippets used for rendering.
+    /// Snippets which are not given keep their default.
+    theme: LatexTheme,
+
+    /// Path to a template for a complete LaTeX document. If not set,
+    /// only the document body is written.
+    document_template: Option<PathBuf>,
+
+    /// Bibliography and citation style of biblatex.
+    bibliography_style: String,
+    /// Path of the BibLaTeX file written by the `bibliography` target,
+    /// as seen from the document. Added to documents which cite works.
+    bibliography_resource: Option<String>,
+    /// Print a bibliography at the end of every article which cites works.
+    article_bibliography: bool,
+
+    /// Emit entries for the back-of-book index?
+    index: bool,
+    /// Terms which are indexed wherever they occur in the text.
+    index_terms: Vec<String>,
 }
 
 impl Default for LatexTarget {
@@ -99,10 +185,144 @@ impl Default for LatexTarget {
                 "explanation" => string_vec!["explanation"]
             ],
             environment_numbers_exceptions: string_vec!["displayquote", "figure"],
+            strings: Box::new(LatexStrings::default()),
+            theme: LatexTheme::default(),
+            document_template: None,
+            bibliography_style: "authoryear".into(),
+            bibliography_resource: None,
+            article_bibliography: false,
+            index: false,
+            index_terms: vec![],
         }
     }
 }
 
+/// all user-facing static strings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LatexStrings {
+    proofcase_caption: String,
+    proofstep_caption: String,
+    definition_caption: String,
+    theorem_caption: String,
+    example_caption: String,
+    exercise_caption: String,
+    hint_caption: String,
+    warning_caption: String,
+    proof_caption: String,
+    alternativeproof_caption: String,
+    proofsummary_caption: String,
+    solution_caption: String,
+    solutionprocess_caption: String,
+    mainarticle_caption: String,
+    /// Prefix of the article titles main articles link to.
+    mainarticle_prefix: String,
+    figure_caption: String,
+    question_caption: String,
+    solutionprocess_env_caption: String,
+    summary_env_caption: String,
+    explanation_env_caption: String,
+    exercise_part_solution_caption: String,
+    /// Introduction of an induction. `<%set%>` stands for its basic set,
+    /// or the `induction_set_default` snippet if it has none.
+    induction_intro: String,
+    induction_base_case: String,
+    induction_hypothesis: String,
+    induction_step: String,
+    induction_step_goal: String,
+    induction_step_proof: String,
+}
+
+impl Default for LatexStrings {
+    fn default() -> LatexStrings {
+        LatexStrings {
+            proofcase_caption: "Fall".into(),
+            proofstep_caption: "Beweisschritt".into(),
+            definition_caption: "Definition".into(),
+            theorem_caption: "Satz".into(),
+            example_caption: "Beispiel".into(),
+            exercise_caption: "Übung".into(),
+            hint_caption: "Hinweis".into(),
+            warning_caption: "Warnung".into(),
+            proof_caption: "Beweis".into(),
+            alternativeproof_caption: "Alternativer Beweis".into(),
+            proofsummary_caption: "Beweiszusammenfassung".into(),
+            solution_caption: "Lösung".into(),
+            solutionprocess_caption: "Lösungsweg".into(),
+            mainarticle_caption: "Hauptartikel".into(),
+            mainarticle_prefix: "Mathe für Nicht-Freaks: ".into(),
+            figure_caption: "Abb.".into(),
+            question_caption: "Frage".into(),
+            solutionprocess_env_caption: "Wie komme ich auf den Beweis?".into(),
+            summary_env_caption: "Zusammenfassung".into(),
+            explanation_env_caption: "Erklärung".into(),
+            exercise_part_solution_caption: "Lösung von Teilaufgabe".into(),
+            induction_intro:
+                "Aussageform, deren Allgemeingültigkeit für <%set%> bewiesen werden soll:".into(),
+            induction_base_case: "Induktionsanfang:".into(),
+            induction_step: "Induktionsschritt:".into(),
+            induction_hypothesis: "Induktionsvoraussetzung:".into(),
+            induction_step_goal: "Induktionsbehauptung:".into(),
+            induction_step_proof: "Beweis des Induktionsschritts:".into(),
+        }
+    }
+}
+
+impl LatexStrings {
+    /// Name of a titled template, used to generate link texts
+    /// like "Satz: Zwischenwertsatz" for links without caption.
+    pub fn template_caption(&self, template: &str) -> Option<&str> {
+        Some(match template {
+            "definition" => &self.definition_caption,
+            "theorem" => &self.theorem_caption,
+            "example" => &self.example_caption,
+            "exercise" => &self.exercise_caption,
+            "hint" => &self.hint_caption,
+            "warning" => &self.warning_caption,
+            "proof" => &self.proof_caption,
+            "alternativeproof" => &self.alternativeproof_caption,
+            "proofsummary" => &self.proofsummary_caption,
+            "solution" => &self.solution_caption,
+            "solutionprocess" => &self.solutionprocess_caption,
+            _ => return None,
+        })
+    }
+
+    /// Escaped introduction of an induction, split at the `<%set%>` placeholder.
+    /// The second part is `None` if the introduction does not mention the set.
+    pub fn induction_intro(&self) -> (String, Option<String>) {
+        let set = placeholder("set");
+        let mut parts = self.induction_intro.splitn(2, &set);
+        let before = LatexRenderer::escape_latex(parts.next().unwrap_or(""));
+        (before, parts.next().map(LatexRenderer::escape_latex))
+    }
+
+    /// Definitions of the captions which are typeset by the document class,
+    /// like `\def\questioncaption{Frage}`.
+    pub fn caption_macros(&self) -> String {
+        let captions = [
+            ("questioncaption", &self.question_caption),
+            (
+                "solutionprocessenvcaption",
+                &self.solutionprocess_env_caption,
+            ),
+            ("summaryenvcaption", &self.summary_env_caption),
+            ("explanationenvcaption", &self.explanation_env_caption),
+            (
+                "exercisepartsolutioncaption",
+                &self.exercise_part_solution_caption,
+            ),
+        ];
+        captions
+            .iter()
+            .map(|(name, value)| {
+                format!("\\def\\{}{{{}}}", name, LatexRenderer::escape_latex(value))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 impl<'a, 's> Target<&'a LatexArgs, &'s Settings> for LatexTarget {
     fn target_type(&self) -> TargetType {
         TargetType::Latex
@@ -115,12 +335,164 @@ impl<'a, 's> Target<&'a LatexArgs, &'s Settings> for LatexTarget {
         args: &'a LatexArgs,
         out: &mut io::Write,
     ) -> io::Result<()> {
+        // anchors are made unique on the tree the `anchors` target sees
+        let (entries, anchors) = collect_anchors(root, &args.document_title);
+
         // apply latex-specific transformations
         let mut latex_tree = root.clone();
         latex_tree = transformations::hoist_thumbnails(latex_tree, ())
             .expect("Error in thumbnail hoisting!");
 
-        let mut renderer = LatexRenderer::new(self, &settings, &args);
-        renderer.run(&latex_tree, (), out)
+        let document_anchors = entries.iter().map(|e| &e.anchor);
+        let labels = LabelMap::new(
+            args.available_anchors
+                .anchors()
+                .chain(document_anchors.clone()),
+        );
+
+        if let Some(ref path) = args.label_map {
+            let mut file = fs::File::create(path)?;
+            labels.write_map(document_anchors, &mut file)?;
+        }
+
+        let footnotes = Footnotes::new(&latex_tree);
+        let mut renderer = LatexRenderer::new(self, &settings, &args, anchors, labels, footnotes);
+        let mut body = vec![];
+        renderer.run(&latex_tree, (), &mut body)?;
+        renderer.remaining_references(&mut body)?;
+        let mut body = String::from_utf8_lossy(&body).to_string();
+
+        if self.article_bibliography && !renderer.citations.is_empty() {
+            body = self
+                .theme
+                .render("article_bibliography", &[("body", body.trim_right())]);
+            body.push('\n');
+        }
+        if let Some(ref path) = args.bibliography {
+            let file = fs::File::create(path)?;
+            serde_json::to_writer(&file, &renderer.citations)
+                .expect("could not serialize bibliography!");
+        }
+
+        let document = match self.document_template {
+            Some(ref path) => {
+                let cites = !renderer.citations.is_empty();
+                self.fill_document_template(path, body, cites, args)?
+            }
+            None => body,
+        };
+
+        if let Some(ref path) = args.source_map {
+            let (document, map) = extract_source_map(&document, &renderer.origins);
+            let file = fs::File::create(path)?;
+            serde_json::to_writer(&file, &map).expect("could not serialize source map!");
+            write!(out, "{}", &document)
+        } else {
+            write!(out, "{}", &document)
+        }
+    }
+}
+
+impl LatexTarget {
+    /// Embed the document body in the document template.
+    fn fill_document_template(
+        &self,
+        template_path: &PathBuf,
+        body: String,
+        cites: bool,
+        args: &LatexArgs,
+    ) -> io::Result<String> {
+        record_input(template_path);
+        let template = fs::read_to_string(template_path)?;
+        let pdf_options: serde_json::Map<String, serde_json::Value> = match args.pdf_options {
+            Some(ref path) => {
+                record_input(path);
+                let file = fs::File::open(path)?;
+                serde_json::from_reader(&file).expect("error reading pdf options:")
+            }
+            None => serde_json::Map::new(),
+        };
+
+        let mut values = HashMap::new();
+        for (key, value) in &pdf_options {
+            values.insert(key.as_str(), json_to_latex(value));
+        }
+        values.insert("body", body);
+        values.insert("title", LatexRenderer::escape_latex(&args.document_title));
+        values.insert("packages", self.package_list(cites));
+        values.insert("captions", self.strings.caption_macros());
+        let bibliography = if cites {
+            self.theme.get("bibliography").to_string()
+        } else {
+            String::new()
+        };
+        values.insert("bibliography", bibliography);
+        let index = if self.index {
+            self.theme.get("print_index").to_string()
+        } else {
+            String::new()
+        };
+        values.insert("index", index);
+
+        fill_placeholders(&template, &values).map_err(|missing| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "document template {:?} has placeholders without value: {}",
+                    template_path,
+                    missing.join(", ")
+                ),
+            )
+        })
+    }
+
+    /// `\usepackage` lines for all required packages.
+    /// Biblatex and its resource are only loaded for documents which cite works,
+    /// the index package only if index entries are emitted.
+    fn package_list(&self, cites: bool) -> String {
+        let biblatex = BIBLATEX_OPTIONS.replace("{}", &self.bibliography_style);
+        let mut packages: Vec<(&str, &str)> = REQUIRED_PACKAGES
+            .iter()
+            .filter(|(_, package)| self.index || *package != "imakeidx")
+            .cloned()
+            .collect();
+        if cites {
+            packages.push((&biblatex, "biblatex"));
+        }
+        let mut lines: Vec<String> = packages
+            .iter()
+            .map(|(options, package)| {
+                if options.is_empty() {
+                    format!("\\usepackage{{{}}}", package)
+                } else {
+                    format!("\\usepackage[{}]{{{}}}", options, package)
+                }
+            })
+            .collect();
+        if let Some(ref path) = self.bibliography_resource {
+            if cites {
+                let resource = self
+                    .theme
+                    .render("bibliography_resource", &[("path", path)]);
+                lines.push(resource);
+            }
+        }
+        if self.index {
+            lines.push(self.theme.get("make_index").to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// Format a pdf option value for use in LaTeX source.
+fn json_to_latex(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(values) => values
+            .iter()
+            .map(json_to_latex)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
     }
 }
@@ -12,7 +12,7 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         for image in &root.content {
             if let Element::InternalReference(ref iref) = *image {
                 let path = mapped_media_path(self.latex.target_type(), &iref.target, self.settings);
-                let caption = iref.caption.render(self)?;
+                let caption = self.render_caption(&iref.caption)?;
 
                 // collect image options
                 let mut image_options = vec![];
@@ -25,14 +25,19 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
                     None => return Ok(false),
                 };
 
-                let inner = format!(
-                    GALLERY_CONTENT!(),
-                    &image_options,
-                    0.9 / (self.latex.gallery_images_per_row as f64),
-                    &license_text,
-                    self.latex.image_height,
-                    &path.to_string_lossy(),
-                    &caption
+                let inner = self.latex.theme.render(
+                    "gallery_content",
+                    &[
+                        ("options", &format!("{:?}", &image_options)),
+                        (
+                            "width",
+                            &(0.9 / (self.latex.gallery_images_per_row as f64)).to_string(),
+                        ),
+                        ("license", &license_text),
+                        ("height", &self.latex.image_height.to_string()),
+                        ("path", &path.to_string_lossy()),
+                        ("caption", &caption),
+                    ],
                 );
                 rendered_images.push(inner);
             }
@@ -1,4 +1,6 @@
 use super::LatexRenderer;
+use crate::anchors::extract_document_anchor;
+use crate::footnotes::{note_label, tag_group, Citation, Note, DEFAULT_GROUP};
 use crate::preamble::*;
 use mediawiki_parser::*;
 
@@ -7,11 +9,25 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         match root.name.to_lowercase().trim() {
             "dfn" => {
                 let content = root.content.render(self)?;
-                write!(out, HTML_ITALIC!(), &content)?;
+                let italic = self
+                    .latex
+                    .theme
+                    .render("html_italic", &[("content", &content)]);
+                let entry = self.index_entry(content.trim(), &extract_plain_text(&root.content));
+                write!(out, "{}{}", &italic, &entry)?;
             }
             "ref" => {
-                let content = root.content.render(self)?;
-                write!(out, HTML_REF!(), &content)?;
+                let citation = self.footnotes.cite(root);
+                self.reference(&citation, out)?;
+            }
+            "references" => {
+                // footnotes of the default group are already placed on their pages
+                // and numbered by LaTeX, so they stay available for reuse.
+                let group = tag_group(root);
+                if group != DEFAULT_GROUP {
+                    let notes = self.footnotes.take_notes(group);
+                    self.references(group, notes, out)?;
+                }
             }
             "section" => (),
             _ => {
@@ -25,4 +41,94 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         }
         Ok(false)
     }
+
+    /// Label of a footnote, to refer to it when it is reused.
+    /// Anchor labels never contain a dot, so footnote labels cannot collide with them.
+    fn footnote_label(&self, citation: &Citation) -> String {
+        let anchor = extract_document_anchor(&self.args.document_title);
+        format!("{}.note-{}", self.labels.label(&anchor), citation.id)
+    }
+
+    /// Write a footnote or, for grouped references, a marker.
+    fn reference(&mut self, citation: &Citation<'e>, out: &mut io::Write) -> io::Result<()> {
+        let latex = self.latex;
+        let theme = &latex.theme;
+        if citation.group != DEFAULT_GROUP {
+            let label = Self::escape_latex(&citation.label());
+            let mark = theme.render("reference_mark", &[("label", &label)]);
+            return write!(out, "{}", &mark);
+        }
+        let label = self.footnote_label(citation);
+        if !citation.is_first_use() {
+            let reference = theme.render("footnote_ref", &[("label", &label)]);
+            return write!(out, "{}", &reference);
+        }
+
+        let content = match citation.content {
+            Some(content) => content.render(self)?,
+            None => return self.undefined_reference(&citation.name, out),
+        };
+        let content = content.trim();
+        if self.defer_footnotes {
+            let text = theme.render("footnote_text", &[("label", &label), ("content", content)]);
+            self.deferred_footnotes.push(text);
+            write!(out, "{}", theme.get("footnote_mark"))
+        } else {
+            let footnote = theme.render("footnote", &[("label", &label), ("content", content)]);
+            write!(out, "{}", &footnote)
+        }
+    }
+
+    /// Write the list of grouped references.
+    pub fn references(
+        &mut self,
+        group: &str,
+        notes: Vec<Note<'e>>,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        if notes.is_empty() {
+            return Ok(());
+        }
+        let mut items = vec![];
+        for note in notes {
+            let label = Self::escape_latex(&note_label(group, note.number));
+            let content = match note.content {
+                Some(content) => content.render(self)?,
+                None => {
+                    let mut error = vec![];
+                    self.undefined_reference(&note.name, &mut error)?;
+                    String::from_utf8(error).unwrap()
+                }
+            };
+            let item = self.latex.theme.render(
+                "reference_item",
+                &[("label", &label), ("content", content.trim())],
+            );
+            items.push(item);
+        }
+        let list = self
+            .latex
+            .theme
+            .render("references", &[("items", &items.join("\n"))]);
+        writeln!(out, "{}", &list)
+    }
+
+    /// Write the grouped references which were not placed by a `<references>` tag.
+    pub fn remaining_references(&mut self, out: &mut io::Write) -> io::Result<()> {
+        for group in self.footnotes.pending_groups() {
+            let notes = self.footnotes.take_notes(&group);
+            if group != DEFAULT_GROUP {
+                self.references(&group, notes, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn undefined_reference(&self, name: &Option<String>, out: &mut io::Write) -> io::Result<()> {
+        let msg = format!(
+            "reference `{}` is used, but never defined!",
+            name.as_ref().map(|n| n.as_str()).unwrap_or("")
+        );
+        self.write_error(&msg, out)
+    }
 }
@@ -2,24 +2,33 @@
 
 use super::LatexRenderer;
 use crate::preamble::*;
-use base64;
+use crate::title;
 use std::path;
 
 impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     pub fn get_license_text(&mut self, root: &'e InternalReference) -> io::Result<Option<String>> {
         let meta = load_media_meta(&root.target, &self.settings);
         let authors = meta.license.authors.join(", ");
-        let license_text = format!(
-            LICENSE_TEXT!(),
-            &Self::escape_latex(&meta.license.url),
-            &Self::escape_latex(
-                &path::PathBuf::from(&meta.license.url)
-                    .file_name()
-                    .map(|f| f.to_string_lossy())
-                    .unwrap_or_default(),
-            ),
-            &Self::escape_latex(&authors),
-            &Self::escape_latex(&meta.license.shortname),
+        let license_text = self.latex.theme.render(
+            "license_text",
+            &[
+                (
+                    "figure",
+                    &Self::escape_latex(&self.latex.strings.figure_caption),
+                ),
+                ("url", &Self::escape_latex(&meta.license.url)),
+                (
+                    "filename",
+                    &Self::escape_latex(
+                        &path::PathBuf::from(&meta.license.url)
+                            .file_name()
+                            .map(|f| f.to_string_lossy())
+                            .unwrap_or_default(),
+                    ),
+                ),
+                ("authors", &Self::escape_latex(&authors)),
+                ("license", &Self::escape_latex(&meta.license.shortname)),
+            ],
         );
         Ok(Some(license_text))
     }
@@ -47,6 +56,8 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
             for option in &root.options {
                 image_options.push(extract_plain_text(option).trim().to_string());
             }
+            let image_options = format!("{:?}", &image_options);
+            let image_path = image_path.to_string_lossy();
 
             if is_thumb(root) {
                 let msg = "Thumbnail images should have been moved into galleries.";
@@ -55,41 +66,50 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
                 return Ok(false);
             }
 
-            let cap_content = &root.caption.render(self)?;
+            let cap_content = &self.render_caption(&root.caption)?;
 
             if is_centered(root) {
                 self.write_def_location(&root.position, doctitle, out)?;
 
-                let mut fig_content = format!(
-                    FIGURE_CONTENT!(),
-                    &image_options,
-                    &license_text,
-                    self.latex.image_width,
-                    self.latex.image_height,
-                    &image_path.to_string_lossy(),
+                let mut fig_content = self.latex.theme.render(
+                    "figure_content",
+                    &[
+                        ("options", &image_options),
+                        ("license", &license_text),
+                        ("width", &self.latex.image_width.to_string()),
+                        ("height", &self.latex.image_height.to_string()),
+                        ("path", &image_path),
+                    ],
                 );
 
                 if self.latex.centered_image_captions || iref_has_option(root, &["from_thumb"]) {
                     fig_content.push('\n');
-                    fig_content.push_str(&format!(FIGURE_CAPTION!(), &cap_content));
+                    fig_content.push_str(
+                        &self
+                            .latex
+                            .theme
+                            .render("figure_caption", &[("caption", &cap_content)]),
+                    );
                 }
 
                 self.environment("figure", &["H"], &fig_content, out)?;
             // inline images
             } else {
-                writeln!(
-                    out,
-                    FIGURE_INLINE!(),
-                    &image_options,
-                    &license_text,
-                    &image_path.to_string_lossy()
-                )?;
+                let inline = self.latex.theme.render(
+                    "figure_inline",
+                    &[
+                        ("options", &image_options),
+                        ("license", &license_text),
+                        ("path", &image_path),
+                    ],
+                );
+                writeln!(out, "{}", &inline)?;
             }
 
             return Ok(false);
         }
 
-        let cap_content = root.caption.render(self)?;
+        let cap_content = self.render_caption(&root.caption)?;
         self.internal_link(&target_str, &cap_content, out)
     }
 
@@ -104,18 +124,53 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         let target = target.trim().trim_left_matches(':').to_string();
 
         // internal references contained in the book.
-        let anchor = matching_anchor(&target, &self.args.available_anchors);
+        let doctitle = &self.args.document_title;
+        let anchor = matching_anchor(&target, doctitle, &self.args.available_anchors);
         if let Some(anchor) = anchor {
-            write!(out, LABEL_REF!(), &base64::encode(&anchor), &caption)?;
+            let caption = if caption.trim().is_empty() {
+                self.anchor_link_text(&anchor, &target)
+            } else {
+                caption.to_string()
+            };
+            let link = self.latex.theme.render(
+                "label_ref",
+                &[
+                    ("label", &self.labels.label(&anchor)),
+                    ("caption", &caption),
+                ],
+            );
+            write!(out, "{}", &link)?;
             return Ok(false);
         }
 
         // other internal references to mediawiki
         let mut url = self.settings.article_url_base.clone();
-        url.push_str(&target);
-        url = Self::escape_latex(&urlencode(&url));
-
-        writeln!(out, INTERNAL_HREF!(), &url, &caption)?;
+        url.push_str(&title::target_anchor(&target, doctitle));
+        url = Self::escape_latex(&url);
+
+        let link = self
+            .latex
+            .theme
+            .render("internal_href", &[("url", &url), ("caption", caption)]);
+        writeln!(out, "{}", &link)?;
         Ok(false)
     }
+
+    /// Generate a link text from the anchor index, like "Satz: Zwischenwertsatz".
+    /// Falls back to the link target if no details are known.
+    fn anchor_link_text(&self, anchor: &str, target: &str) -> String {
+        let entry = match self.args.available_anchors.entry(anchor) {
+            Some(entry) => entry,
+            None => return Self::escape_latex(target),
+        };
+        let caption = Self::escape_latex(&entry.caption);
+        let template_caption = entry
+            .template
+            .as_ref()
+            .and_then(|t| self.latex.strings.template_caption(t));
+        match template_caption {
+            Some(name) => format!("{}: {}", Self::escape_latex(name), caption),
+            None => caption,
+        }
+    }
 }
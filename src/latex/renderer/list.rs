@@ -38,13 +38,17 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
                     }
 
                     let item = if let ListItemKind::Definition = li.kind {
-                        format!(
-                            ITEM_DEFINITION!(),
-                            definition_term.unwrap_or_default(),
-                            child_content.trim()
+                        self.latex.theme.render(
+                            "item_definition",
+                            &[
+                                ("term", &definition_term.unwrap_or_default()),
+                                ("content", child_content.trim()),
+                            ],
                         )
                     } else {
-                        format!(ITEM!(), child_content.trim())
+                        self.latex
+                            .theme
+                            .render("item", &[("content", child_content.trim())])
                     };
 
                     let line_width = self.latex.max_line_width;
@@ -57,7 +61,11 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
             items
         };
 
-        writeln!(out, LIST!(), envname, &items.join("\n"), envname)?;
+        let list = self
+            .latex
+            .theme
+            .render("list", &[("env", envname), ("items", &items.join("\n"))]);
+        writeln!(out, "{}", &list)?;
         Ok(false)
     }
 }
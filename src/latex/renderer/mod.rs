@@ -1,9 +1,10 @@
 //! Definition of the LaTeX renderer. Subfunctions are implemented in other files.
 
-use super::LatexTarget;
+use super::{escape_index, index_sort_key, mark_origin, BibEntry, LatexTarget, SourceOrigin};
+use crate::anchors::AnchorRegistry;
+use crate::footnotes::Footnotes;
+use crate::labels::LabelMap;
 use crate::preamble::*;
-#[macro_use]
-mod blobs;
 
 mod gallery;
 mod html;
@@ -23,46 +24,69 @@ pub struct LatexRenderer<'e, 't, 's: 'e, 'a> {
     pub settings: &'s Settings,
     pub args: &'a LatexArgs,
 
+    /// Unique anchors of the rendered document.
+    pub anchors: AnchorRegistry,
+    /// Labels of all anchors in the export.
+    pub labels: LabelMap,
+
+    /// Numbering of the references in the document.
+    pub footnotes: Footnotes<'e>,
+    /// Works cited by `Literature` templates, in order of their first citation.
+    pub citations: Vec<BibEntry>,
+
     /// Render paragraphs as normal text, without newline.
     pub flatten_paragraphs: bool,
+    /// Use `\footnotemark` and collect the footnote texts, where `\footnote` does not work.
+    pub defer_footnotes: bool,
+    /// Footnote texts to write after the current heading or table.
+    pub deferred_footnotes: Vec<String>,
+    /// Rendering a heading, caption or link text, where configured terms are not indexed.
+    pub in_caption: bool,
+
+    /// Origins of marked output, collected if a source map is written.
+    pub origins: Vec<SourceOrigin>,
+    /// Articles of the sections currently included, innermost last.
+    pub included: Vec<String>,
 }
 
 impl<'e, 's: 'e, 't: 'e, 'a> Traversion<'e, ()> for LatexRenderer<'e, 't, 's, 'a> {
     path_methods!('e);
 
     fn work(&mut self, root: &'e Element, _: (), out: &mut io::Write) -> io::Result<bool> {
-        Ok(match *root {
-            // Node elements
-            Element::Document(ref root) => self.document(root, out)?,
-            Element::Heading(ref root) => self.heading(root, out)?,
-            Element::Formatted(ref root) => self.formatted(root, out)?,
-            Element::Paragraph(ref root) => self.paragraph(root, out)?,
-            Element::Template(ref root) => self.template(root, out)?,
-            Element::TemplateArgument(ref root) => self.template_arg(root, out)?,
-            Element::InternalReference(ref root) => self.internal_ref(root, out)?,
-            Element::List(ref root) => self.list(root, out)?,
-            Element::HtmlTag(ref root) => self.htmltag(root, out)?,
-            Element::Gallery(ref root) => self.gallery(root, out)?,
-            Element::ExternalReference(ref root) => self.href(root, out)?,
-            Element::Table(ref root) => self.table(root, out)?,
-            Element::TableRow(ref root) => self.table_row(root, out)?,
-            Element::TableCell(ref root) => self.table_cell(root, out)?,
+        let is_block = match root {
+            Element::Heading(_)
+            | Element::Paragraph(_)
+            | Element::Template(_)
+            | Element::InternalReference(_)
+            | Element::List(_)
+            | Element::HtmlTag(_)
+            | Element::Gallery(_)
+            | Element::Table(_)
+            | Element::TableRow(_) => true,
+            _ => false,
+        };
+        if self.args.source_map.is_none() || !is_block {
+            return self.render_element(root, out);
+        }
 
-            // Leaf Elements
-            Element::Text(ref root) => self.text(root, out)?,
-            Element::Comment(ref root) => self.comment(root, out)?,
-            Element::Error(ref root) => self.error(root, out)?,
-            _ => {
-                self.write_error(
-                    &format!(
-                        "export for element `{}` not implemented!",
-                        root.get_variant_name()
-                    ),
-                    out,
-                )?;
-                false
-            }
-        })
+        let mut output = vec![];
+        let recurse = self.render_element(root, &mut output)?;
+        let document = self
+            .included
+            .last()
+            .unwrap_or(&self.args.document_title)
+            .clone();
+        self.origins.push(SourceOrigin {
+            document,
+            position: root.get_position().clone(),
+        });
+        let id = self.origins.len() - 1;
+        write!(
+            out,
+            "{}",
+            mark_origin(&String::from_utf8_lossy(&output), id)
+        )?;
+        Ok(recurse)
     }
 
     /// Handle paragraph line breaks correctly.
@@ -103,16 +127,64 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         target: &'t LatexTarget,
         settings: &'s Settings,
         args: &'a LatexArgs,
+        anchors: AnchorRegistry,
+        labels: LabelMap,
+        footnotes: Footnotes<'e>,
     ) -> LatexRenderer<'e, 't, 's, 'a> {
         LatexRenderer {
             flatten_paragraphs: false,
+            defer_footnotes: false,
+            deferred_footnotes: vec![],
+            in_caption: false,
+            footnotes,
+            citations: vec![],
             path: vec![],
             latex: target,
             settings,
             args,
+            anchors,
+            labels,
+            origins: vec![],
+            included: vec![],
         }
     }
 
+    /// Render an element with the matching function.
+    fn render_element(&mut self, root: &'e Element, out: &mut io::Write) -> io::Result<bool> {
+        Ok(match *root {
+            // Node elements
+            Element::Document(ref root) => self.document(root, out)?,
+            Element::Heading(ref root) => self.heading(root, out)?,
+            Element::Formatted(ref root) => self.formatted(root, out)?,
+            Element::Paragraph(ref root) => self.paragraph(root, out)?,
+            Element::Template(ref root) => self.template(root, out)?,
+            Element::TemplateArgument(ref root) => self.template_arg(root, out)?,
+            Element::InternalReference(ref root) => self.internal_ref(root, out)?,
+            Element::List(ref root) => self.list(root, out)?,
+            Element::HtmlTag(ref root) => self.htmltag(root, out)?,
+            Element::Gallery(ref root) => self.gallery(root, out)?,
+            Element::ExternalReference(ref root) => self.href(root, out)?,
+            Element::Table(ref root) => self.table(root, out)?,
+            Element::TableRow(ref root) => self.table_row(root, out)?,
+            Element::TableCell(ref root) => self.table_cell(root, out)?,
+
+            // Leaf Elements
+            Element::Text(ref root) => self.text(root, out)?,
+            Element::Comment(ref root) => self.comment(root, out)?,
+            Element::Error(ref root) => self.error(root, out)?,
+            _ => {
+                self.write_error(
+                    &format!(
+                        "export for element `{}` not implemented!",
+                        root.get_variant_name()
+                    ),
+                    out,
+                )?;
+                false
+            }
+        })
+    }
+
     /// Escape LaTeX-Specific symbols
     pub fn escape_latex(input: &str) -> String {
         let mut res = String::new();
@@ -155,6 +227,56 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         Ok(())
     }
 
+    /// Entry of the back-of-book index for a rendered term, sorted by `plain_text`.
+    fn index_entry(&self, term: &str, plain_text: &str) -> String {
+        let sort = index_sort_key(plain_text);
+        if !self.latex.index || term.trim().is_empty() || sort.is_empty() {
+            return String::new();
+        }
+        self.latex.theme.render(
+            "index_entry",
+            &[
+                ("sort", &escape_index(&sort)),
+                ("term", &escape_index(term.trim())),
+            ],
+        )
+    }
+
+    /// Render a heading, caption or link text.
+    fn render_caption(&mut self, caption: &'e [Element]) -> io::Result<String> {
+        let previous = self.in_caption;
+        self.in_caption = true;
+        let result = caption.render(self);
+        self.in_caption = previous;
+        result
+    }
+
+    /// Start collecting footnote texts instead of writing them in place.
+    /// Returns whether footnotes were deferred before.
+    fn start_deferring_footnotes(&mut self) -> bool {
+        let previous = self.defer_footnotes;
+        self.defer_footnotes = true;
+        previous
+    }
+
+    /// Stop deferring footnotes and take the collected footnote texts,
+    /// unless an enclosing element still defers them. The texts are preceded
+    /// by rewinding the footnote counter to the first of their marks.
+    fn stop_deferring_footnotes(&mut self, previous: bool) -> Vec<String> {
+        self.defer_footnotes = previous;
+        if previous || self.deferred_footnotes.is_empty() {
+            return vec![];
+        }
+        let count = self.deferred_footnotes.len().to_string();
+        let rewind = self
+            .latex
+            .theme
+            .render("footnote_rewind", &[("count", &count)]);
+        let mut texts = vec![rewind];
+        texts.extend(self.deferred_footnotes.drain(..));
+        texts
+    }
+
     fn environment(
         &self,
         name: &str,
@@ -177,7 +299,15 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         } else {
             format!("{}*", name)
         };
-        writeln!(out, GENERIC_ENV!(), &name, &arg_string, content, name)
+        let environment = self.latex.theme.render(
+            "generic_env",
+            &[
+                ("name", &name),
+                ("args", &arg_string),
+                ("content", &content),
+            ],
+        );
+        writeln!(out, "{}", &environment)
     }
 
     fn write_error(&self, message: &str, out: &mut io::Write) -> io::Result<()> {
@@ -2,8 +2,9 @@
 
 use super::LatexRenderer;
 use crate::anchors::{extract_document_anchor, extract_heading_anchor};
+use crate::compose::{INCLUSION_END, INCLUSION_START};
+use crate::latex::find_terms;
 use crate::preamble::*;
-use base64;
 use mediawiki_parser::MarkupType;
 
 impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
@@ -21,32 +22,55 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         let line_width = self.latex.max_line_width;
         let indent = self.latex.indentation_depth;
 
-        let caption = root.caption.render(self)?;
+        let deferring = self.start_deferring_footnotes();
+        let caption = self.render_caption(&root.caption)?;
+        let footnote_texts = self.stop_deferring_footnotes(deferring);
         let content = root.content.render(self)?;
 
         let content = indent_and_trim(&content, indent, line_width);
         let depth_string = "sub".repeat(root.depth - 1);
 
         let anchor = extract_heading_anchor(root, &self.args.document_title);
+        let anchor = self.anchors.resolve(&anchor, &root.position);
 
-        writeln!(out, SECTION!(), depth_string, caption.trim())?;
+        let theme = &self.latex.theme;
+        let section = theme.render(
+            "section",
+            &[("depth", &depth_string), ("caption", caption.trim())],
+        );
+        writeln!(out, "{}", &section)?;
         write!(out, "{}", " ".repeat(indent))?;
-        write!(out, LABEL!(), base64::encode(&anchor))?;
+        let label = theme.render("label", &[("label", &self.labels.label(&anchor))]);
+        write!(out, "{}", &label)?;
         writeln!(out, "{}", &self.latex.post_heading_space)?;
+        for text in &footnote_texts {
+            writeln!(out, "{}", text)?;
+        }
         writeln!(out, "{}", &content.trim_right())?;
         Ok(false)
     }
 
     pub fn document(&mut self, _root: &'e Document, out: &mut io::Write) -> io::Result<bool> {
-        writeln!(
-            out,
-            LABEL!(),
-            base64::encode(&extract_document_anchor(&self.args.document_title))
-        )?;
+        let anchor = extract_document_anchor(&self.args.document_title);
+        let label = self
+            .latex
+            .theme
+            .render("label", &[("label", &self.labels.label(&anchor))]);
+        writeln!(out, "{}", &label)?;
         Ok(true)
     }
 
     pub fn comment(&mut self, root: &'e Comment, out: &mut io::Write) -> io::Result<bool> {
+        // keep track of included sections for the source map and footnotes
+        self.footnotes.comment(root);
+        let text = root.text.trim();
+        if text.starts_with(INCLUSION_START) {
+            let article = text[INCLUSION_START.len()..].split('|').next();
+            self.included.push(article.unwrap_or("").to_string());
+        } else if text.starts_with(INCLUSION_END) {
+            self.included.pop();
+        }
+
         // TODO: Comments can currently cause errors with flattened paragraphs,
         // eating up following LaTeX.
         if !self.flatten_paragraphs {
@@ -56,35 +80,48 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     }
 
     pub fn text(&mut self, root: &'e Text, out: &mut io::Write) -> io::Result<bool> {
-        write!(out, "{}", &Self::escape_latex(&root.text))?;
+        // index configured terms after each occurrence,
+        // but not in captions, where `\index` breaks moving arguments.
+        let mut written = 0;
+        if self.latex.index && !self.in_caption {
+            for (end, term) in find_terms(&root.text, &self.latex.index_terms) {
+                let entry = self.index_entry(&Self::escape_latex(term), term);
+                write!(out, "{}", &Self::escape_latex(&root.text[written..end]))?;
+                write!(out, "{}", &entry)?;
+                written = end;
+            }
+        }
+        write!(out, "{}", &Self::escape_latex(&root.text[written..]))?;
         Ok(false)
     }
 
     pub fn formatted(&mut self, root: &'e Formatted, out: &mut io::Write) -> io::Result<bool> {
         let inner = root.content.render(self)?;
 
+        let theme = &self.latex.theme;
         match root.markup {
             MarkupType::NoWiki => {
                 write!(out, "{}", &inner)?;
             }
             MarkupType::Bold => {
-                write!(out, BOLD!(), &inner)?;
+                write!(out, "{}", theme.render("bold", &[("content", &inner)]))?;
             }
             MarkupType::Italic => {
-                write!(out, ITALIC!(), &inner)?;
+                write!(out, "{}", theme.render("italic", &[("content", &inner)]))?;
             }
             MarkupType::Math => {
                 let inner = extract_plain_text(&root.content);
-                write!(out, MATH!(), &inner)?;
+                write!(out, "{}", theme.render("math", &[("content", &inner)]))?;
             }
             MarkupType::StrikeThrough => {
-                write!(out, STRIKE_THROUGH!(), &inner)?;
+                let inner = theme.render("strike_through", &[("content", &inner)]);
+                write!(out, "{}", &inner)?;
             }
             MarkupType::Underline => {
-                write!(out, UNDERLINE!(), &inner)?;
+                write!(out, "{}", theme.render("underline", &[("content", &inner)]))?;
             }
             MarkupType::Blockquote => {
-                self.environment(QUOTE_ENV!(), &[], &inner, out)?;
+                self.environment(theme.get("quote_env"), &[], &inner, out)?;
             }
             _ => {
                 let msg = format!("MarkupType not implemented: {:?}", &root.markup);
@@ -95,12 +132,16 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     }
 
     pub fn href(&mut self, root: &'e ExternalReference, out: &mut io::Write) -> io::Result<bool> {
-        let mut caption = root.caption.render(self)?;
+        let mut caption = self.render_caption(&root.caption)?;
         if caption.is_empty() {
             caption = Self::escape_latex(&root.target);
         }
         let url = Self::escape_latex(&urlencode(&root.target));
-        writeln!(out, INTERNAL_HREF!(), &url, &caption)?;
+        let link = self
+            .latex
+            .theme
+            .render("internal_href", &[("url", &url), ("caption", &caption)]);
+        writeln!(out, "{}", &link)?;
         Ok(false)
     }
 }
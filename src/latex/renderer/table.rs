@@ -89,12 +89,13 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
 
         let columns = "X[l]".repeat(table_info.width);
 
+        let deferring = self.start_deferring_footnotes();
         let content = if let Some(header) = table_info.header {
-            format!(
-                TABLE_WITH_HEADER!(),
-                header.render(self)?,
-                table_info.body.render(self)?,
-            )
+            let header = header.render(self)?;
+            let body = table_info.body.render(self)?;
+            self.latex
+                .theme
+                .render("table_with_header", &[("header", &header), ("body", &body)])
         } else {
             table_info.body.render(self)?
         };
@@ -102,13 +103,23 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
         let line_width = self.latex.max_line_width;
         let indent = self.latex.indentation_depth;
 
-        writeln!(
-            out,
-            TABLE!(),
-            columns,
-            root.caption.render(self)?.trim(),
-            &indent_and_trim(content.trim(), indent, line_width),
-        )?;
+        let caption = self.render_caption(&root.caption)?;
+        let footnote_texts = self.stop_deferring_footnotes(deferring);
+        let table = self.latex.theme.render(
+            "table",
+            &[
+                ("columns", &columns),
+                ("caption", caption.trim()),
+                (
+                    "content",
+                    &indent_and_trim(content.trim(), indent, line_width),
+                ),
+            ],
+        );
+        writeln!(out, "{}", &table)?;
+        for text in &footnote_texts {
+            writeln!(out, "{}", text)?;
+        }
 
         Ok(false)
     }
@@ -2,13 +2,17 @@
 
 use super::LatexRenderer;
 use crate::anchors::extract_template_anchor;
+use crate::latex::BibEntry;
 use crate::preamble::*;
-use base64;
 use mfnf_template_spec::*;
 use mwparser_utils::*;
 
 impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     pub fn template(&mut self, root: &'e Template, out: &mut io::Write) -> io::Result<bool> {
+        if is_index_template(root) {
+            self.index_template(root, out)?;
+            return Ok(false);
+        }
         let doctitle = &self.args.document_title;
         let parsed = if let Some(parsed) = parse_template(&root) {
             parsed
@@ -38,10 +42,12 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
             | KnownTemplate::AlternativeProof(_)
             | KnownTemplate::ProofSummary(_)
             | KnownTemplate::Solution(_)
-            | KnownTemplate::SolutionProcess(_) => self.environment_template(&parsed, out)?,
+            | KnownTemplate::SolutionProcess(_) => {
+                self.environment_template(&parsed, &root.position, out)?
+            }
             KnownTemplate::GroupExercise(group) => self.group_exercise(&group, out)?,
             KnownTemplate::ProofStep(step) => self.proofstep(&step, out)?,
-            KnownTemplate::Anchor(_) => self.anchor(&parsed, out)?,
+            KnownTemplate::Anchor(_) => self.anchor(&parsed, &root.position, out)?,
             KnownTemplate::Mainarticle(article) => self.mainarticle(&article, out)?,
             KnownTemplate::Navigation(_) => (),
             KnownTemplate::Question(question) => self.question(&question, out)?,
@@ -87,19 +93,20 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
 
         let mut trimmed = trim_enclosing(&content, "\\begin{align}", "\\end{align}");
         trimmed = trim_enclosing(trimmed, "\\begin{align*}", "\\end{align*}").trim();
-        self.environment(MATH_ENV!(), &[], trimmed, out)
+        self.environment(self.latex.theme.get("math_env"), &[], trimmed, out)
     }
 
     fn proofstep(&mut self, step: &ProofStep<'e>, out: &mut io::Write) -> io::Result<()> {
         let name = match step.name {
             Some(name) => name.render(self)?,
-            None => "Beweisschritt".into(),
+            None => Self::escape_latex(&self.latex.strings.proofstep_caption),
         };
         let goal = step.goal.render(self)?;
         let step = step.step.render(self)?;
         let separator = &self.latex.paragraph_separator;
         let body = format!("{}{}\n{}", goal.trim(), separator, step);
-        self.environment(PROOF_STEP_ENV!(), &[name.trim()], body.trim(), out)
+        let env = self.latex.theme.get("proof_step_env");
+        self.environment(env, &[name.trim()], body.trim(), out)
     }
 
     fn todo(&mut self, todo: &Todo<'e>, out: &mut io::Write) -> io::Result<()> {
@@ -111,44 +118,51 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     }
 
     fn literature(&mut self, literature: &Literature<'e>, out: &mut io::Write) -> io::Result<()> {
-        let mut lit = String::new();
-        if let Some(author) = literature.author {
-            lit.push_str(&extract_plain_text(author));
-            lit.push_str(": ");
+        let entry = BibEntry::from_literature(literature);
+        let pages = literature
+            .pages
+            .map(|p| Self::escape_latex(extract_plain_text(p).trim()))
+            .unwrap_or_default();
+        let citation = self
+            .latex
+            .theme
+            .render("citation", &[("key", &entry.key), ("pages", &pages)]);
+        if !self.citations.contains(&entry) {
+            self.citations.push(entry);
         }
-        lit.push_str(&format!(
-            "\\emph{{{}}}",
-            &extract_plain_text(literature.title)
-        ));
+        write!(out, "{}", &citation)
+    }
 
-        if let Some(publisher) = literature.publisher {
-            lit.push_str(". ");
-            lit.push_str(&extract_plain_text(publisher));
-        }
-        if let Some(address) = literature.address {
-            lit.push_str(", ");
-            lit.push_str(&extract_plain_text(address));
-        }
-        if let Some(year) = literature.year {
-            lit.push_str(" ");
-            lit.push_str(&extract_plain_text(year));
-        }
-        if let Some(isbn) = literature.isbn {
-            lit.push_str(", ");
-            lit.push_str(&extract_plain_text(isbn));
-        }
-        if let Some(pages) = literature.pages {
-            lit.push_str(", ");
-            lit.push_str(&format!("S. {}", &extract_plain_text(pages)));
+    /// An extra index entry, given by the first argument and an optional `sort` key.
+    fn index_template(&mut self, root: &'e Template, out: &mut io::Write) -> io::Result<()> {
+        let mut term = None;
+        let mut sort = None;
+        for argument in &root.content {
+            if let Element::TemplateArgument(ref argument) = *argument {
+                match argument.name.trim().to_lowercase().as_str() {
+                    "1" | "term" => term = Some(&argument.value),
+                    "sort" => sort = Some(&argument.value),
+                    _ => (),
+                }
+            }
         }
-        lit.push_str(".");
-        write!(out, "{}", lit)
+        let term = match term {
+            Some(term) => term,
+            None => {
+                self.write_def_location(&root.position, &self.args.document_title, out)?;
+                return self.write_error("index template without a term!", out);
+            }
+        };
+        let rendered = term.render(self)?;
+        let sort = extract_plain_text(sort.unwrap_or(term));
+        let entry = self.index_entry(rendered.trim(), &sort);
+        write!(out, "{}", &entry)
     }
 
     fn question(&mut self, question: &Question<'e>, out: &mut io::Write) -> io::Result<()> {
         let title = match question.kind {
             Some(e) => e.render(self)?,
-            None => String::new(),
+            None => Self::escape_latex(&self.latex.strings.question_caption),
         };
         let question_text = question.question.render(self)?;
         let answer = question.answer.render(self)?;
@@ -174,17 +188,19 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
             if let (Some(case), Some(proof)) = tuple {
                 let goal = case.render(self)?;
                 let proof = proof.render(self)?;
-                let name = format!("Fall {}", index + 1);
+                let caption = Self::escape_latex(&self.latex.strings.proofcase_caption);
+                let name = format!("{} {}", caption, index + 1);
                 let sep = &self.latex.paragraph_separator;
                 let content = format!("{}{}\n{}", goal.trim(), sep, proof.trim());
-                self.environment(PROOF_CASE_ENV!(), &[&name], &content, out)?;
+                let env = self.latex.theme.get("proof_case_env");
+                self.environment(env, &[&name], &content, out)?;
             }
         }
         Ok(())
     }
 
     fn group_exercise(&mut self, group: &GroupExercise<'e>, out: &mut io::Write) -> io::Result<()> {
-        let title = group.title.unwrap_or(&[]).render(self)?;
+        let title = self.render_caption(group.title.unwrap_or(&[]))?;
 
         let tasks;
         let solutions;
@@ -216,20 +232,33 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
 
             let task_list = build_items(false);
             let solution_list = build_items(true);
-            tasks = format!(LIST!(), "enumerate", &task_list.join("\n"), "enumerate");
-            solutions = format!(LIST!(), "enumerate", &solution_list.join("\n"), "enumerate");
+            let theme = &self.latex.theme;
+            tasks = theme.render(
+                "list",
+                &[("env", "enumerate"), ("items", &task_list.join("\n"))],
+            );
+            solutions = theme.render(
+                "list",
+                &[("env", "enumerate"), ("items", &solution_list.join("\n"))],
+            );
         }
 
         let mut exercise = if let Some(exercise_raw) = group.exercise {
             let exercise = exercise_raw.render(self)?;
-            format!(EXERCISE_TASKLIST!(), exercise.trim(), tasks.trim())
+            self.latex.theme.render(
+                "exercise_tasklist",
+                &[("exercise", exercise.trim()), ("tasks", tasks.trim())],
+            )
         } else {
             String::new()
         };
 
         if let Some(explanation) = group.explanation {
             let exp = explanation.render(self)?;
-            exercise = format!(EXERCISE_EXPLANATION!(), exercise.trim(), exp.trim());
+            exercise = self.latex.theme.render(
+                "exercise_explanation",
+                &[("exercise", exercise.trim()), ("explanation", exp.trim())],
+            );
         }
 
         self.environment("exercise", &[title.trim()], exercise.trim(), out)?;
@@ -238,37 +267,71 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     }
 
     fn induction(&mut self, induction: &Induction<'e>, out: &mut io::Write) -> io::Result<()> {
-        let basic = if let Some(e) = induction.basic_set {
-            e.render(self)?
-        } else {
-            INDUCTION_SET_DEFAULT!().to_string()
+        let (intro, intro_end) = self.latex.strings.induction_intro();
+        let set = match (induction.basic_set, &intro_end) {
+            (_, None) => String::new(),
+            (Some(e), _) => e.render(self)?,
+            (None, _) => self.latex.theme.get("induction_set_default").to_string(),
         };
         let statement = induction.statement.render(self)?;
         let base_case = induction.base_case.render(self)?;
         let hypothesis = induction.induction_hypothesis.render(self)?;
         let step_case_goal = induction.step_case_goal.render(self)?;
         let step_case = induction.step_case.render(self)?;
-        writeln!(
-            out,
-            INDUCTION!(),
-            basic.trim(),
-            statement.trim(),
-            base_case.trim(),
-            hypothesis.trim(),
-            step_case_goal.trim(),
-            step_case.trim()
-        )
+        let strings = &self.latex.strings;
+        let induction = self.latex.theme.render(
+            "induction",
+            &[
+                ("intro", &intro),
+                ("set", set.trim()),
+                ("intro_end", &intro_end.unwrap_or_default()),
+                (
+                    "base_case_caption",
+                    &Self::escape_latex(&strings.induction_base_case),
+                ),
+                ("step_caption", &Self::escape_latex(&strings.induction_step)),
+                (
+                    "hypothesis_caption",
+                    &Self::escape_latex(&strings.induction_hypothesis),
+                ),
+                (
+                    "step_case_goal_caption",
+                    &Self::escape_latex(&strings.induction_step_goal),
+                ),
+                (
+                    "step_case_caption",
+                    &Self::escape_latex(&strings.induction_step_proof),
+                ),
+                ("statement", statement.trim()),
+                ("base_case", base_case.trim()),
+                ("hypothesis", hypothesis.trim()),
+                ("step_case_goal", step_case_goal.trim()),
+                ("step_case", step_case.trim()),
+            ],
+        );
+        writeln!(out, "{}", &induction)
     }
 
     fn important(&mut self, template: &Important<'e>, out: &mut io::Write) -> io::Result<()> {
         let content = template.content.render(self)?;
-        self.environment(IMPORTANT_ENV!(), &[], content.trim(), out)
+        let env = self.latex.theme.get("important_env");
+        self.environment(env, &[], content.trim(), out)
     }
 
-    fn anchor(&self, root: &'e KnownTemplate, out: &mut io::Write) -> io::Result<()> {
+    fn anchor(
+        &mut self,
+        root: &'e KnownTemplate,
+        position: &Span,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
         let doctitle = &self.args.document_title;
         if let Some(anchor) = extract_template_anchor(root, doctitle) {
-            write!(out, LABEL!(), base64::encode(&anchor))?;
+            let anchor = self.anchors.resolve(&anchor, position);
+            let label = self
+                .latex
+                .theme
+                .render("label", &[("label", &self.labels.label(&anchor))]);
+            write!(out, "{}", &label)?;
         } else {
             self.write_error("anchor export could not extract an anchor?", out)?;
         }
@@ -276,9 +339,14 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     }
 
     fn mainarticle(&mut self, template: &Mainarticle<'e>, out: &mut io::Write) -> io::Result<()> {
-        write!(out, MAINARTICLE!())?;
+        let caption = Self::escape_latex(&self.latex.strings.mainarticle_caption);
+        let intro = self
+            .latex
+            .theme
+            .render("mainarticle", &[("caption", &caption)]);
+        write!(out, "{}", &intro)?;
         let caption = extract_plain_text(&template.article);
-        let mut target = "Mathe für Nicht-Freaks: ".to_string();
+        let mut target = self.latex.strings.mainarticle_prefix.clone();
         target.push_str(&caption);
 
         self.internal_link(&target, &caption, out)?;
@@ -297,17 +365,35 @@ impl<'e, 's: 'e, 't: 'e, 'a> LatexRenderer<'e, 't, 's, 'a> {
     pub fn environment_template(
         &mut self,
         template: &KnownTemplate<'e>,
+        position: &Span,
         out: &mut io::Write,
     ) -> io::Result<()> {
         let title = template.find("title").map(|a| a.value).unwrap_or(&[]);
-        let title_text = title.render(self)?;
+        let title_text = self.render_caption(title)?;
         let doctitle = &self.args.document_title;
 
         if let Some(anchor) = extract_template_anchor(template, doctitle) {
-            write!(out, LABEL!(), base64::encode(&anchor))?;
+            let anchor = self.anchors.resolve(&anchor, position);
+            let label = self
+                .latex
+                .theme
+                .render("label", &[("label", &self.labels.label(&anchor))]);
+            write!(out, "{}", &label)?;
             writeln!(out, "%")?
         }
 
+        // definitions and theorems are indexed by their title
+        let indexed = match template {
+            KnownTemplate::Definition(_) | KnownTemplate::Theorem(_) => true,
+            _ => false,
+        };
+        if indexed {
+            let entry = self.index_entry(title_text.trim(), &extract_plain_text(title));
+            if !entry.is_empty() {
+                writeln!(out, "{}%", &entry)?;
+            }
+        }
+
         for attribute in template.present() {
             if attribute.name == "title" {
                 continue;
@@ -0,0 +1,103 @@
+//! Map from lines of the LaTeX output back to positions in the wiki source.
+//!
+//! While rendering, the output of block elements is enclosed in hidden
+//! markers referring to their origin. After rendering, the markers are
+//! removed and the line ranges of each origin are collected.
+
+use crate::preamble::*;
+
+/// Where a piece of output was generated from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceOrigin {
+    /// Article of the source, differs from the exported document for included sections.
+    pub document: String,
+    pub position: Span,
+}
+
+/// A range of output lines and their origin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceMapEntry {
+    /// First line of the range, starting at 1.
+    pub start_line: usize,
+    /// Last line of the range (inclusive).
+    pub end_line: usize,
+    pub document: String,
+    pub position: Span,
+}
+
+/// Enclose rendered output in markers of the origin with index `id`.
+/// Surrounding whitespace stays outside, so trimming the output is not affected.
+pub fn mark_origin(output: &str, id: usize) -> String {
+    let content = output.trim();
+    if content.is_empty() {
+        return output.to_string();
+    }
+    let start = output.len() - output.trim_left().len();
+    let end = start + content.len();
+    let open = format!("{}{}{}", HIDDEN_START, id, HIDDEN_END);
+    let close = format!("{}/{}{}", HIDDEN_START, id, HIDDEN_END);
+    format!(
+        "{}{}{}{}{}",
+        &output[..start],
+        open,
+        content,
+        close,
+        &output[end..]
+    )
+}
+
+/// Remove the origin markers from the output and collect the line ranges
+/// of each origin. A line belongs to the innermost origin of its content.
+pub fn extract_source_map(output: &str, origins: &[SourceOrigin]) -> (String, Vec<SourceMapEntry>) {
+    let mut lines = vec![];
+    let mut owners = vec![];
+    let mut open: Vec<usize> = vec![];
+
+    for line in output.split('\n') {
+        let mut clean = String::new();
+        let mut owner = None;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if c == HIDDEN_START {
+                let marker: String = chars.by_ref().take_while(|c| *c != HIDDEN_END).collect();
+                if marker.starts_with('/') {
+                    open.pop();
+                } else if let Ok(id) = marker.parse() {
+                    open.push(id);
+                }
+                continue;
+            }
+            if !c.is_whitespace() {
+                owner = open.last().cloned();
+            }
+            clean.push(c);
+        }
+        if clean.trim().is_empty() {
+            owner = open.last().cloned();
+        }
+        lines.push(clean);
+        owners.push(owner);
+    }
+
+    let mut entries: Vec<SourceMapEntry> = vec![];
+    let mut previous = None;
+    for (index, owner) in owners.iter().enumerate() {
+        let line = index + 1;
+        match owner.and_then(|id| origins.get(id)) {
+            Some(_) if previous == *owner => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.end_line = line;
+                }
+            }
+            Some(origin) => entries.push(SourceMapEntry {
+                start_line: line,
+                end_line: line,
+                document: origin.document.clone(),
+                position: origin.position.clone(),
+            }),
+            None => (),
+        }
+        previous = *owner;
+    }
+    (lines.join("\n"), entries)
+}
@@ -0,0 +1,489 @@
+//! The LaTeX theme keeps all blobs of LaTeX source code in one place.
+//!
+//! Every snippet is a piece of LaTeX with named `<%placeholders%>`, which are
+//! filled in by the renderer. The defaults can be overridden in the `theme`
+//! option of the `latex` target. Overrides are checked when the configuration
+//! is loaded, so a broken theme fails before anything is rendered.
+
+use crate::util::{fill_placeholders, placeholders};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+
+/// A LaTeX snippet and the placeholders it may use.
+pub struct Snippet {
+    pub name: &'static str,
+    /// Placeholders every definition of the snippet must contain.
+    pub required: &'static [&'static str],
+    /// Placeholders which may be left out.
+    pub optional: &'static [&'static str],
+    pub default: &'static str,
+}
+
+/// All snippets of the LaTeX output.
+pub const SNIPPETS: &[Snippet] = &[
+    // --- Environments ---
+    Snippet {
+        name: "generic_env",
+        required: &["name", "content"],
+        optional: &["args"],
+        default: "\\begin{<%name%>}<%args%>
+<%content%>
+\\end{<%name%>}%",
+    },
+    // --- Internal References ---
+    Snippet {
+        name: "license_text",
+        required: &["url", "filename", "authors", "license"],
+        optional: &["figure"],
+        default: "<%figure%> \\arabic{imagelabel}: \
+                  \\protect\\href{<%url%>}{\\textbf{<%filename%>}} by <%authors%> \
+                  \\textit{(<%license%>)}",
+    },
+    Snippet {
+        name: "figure_content",
+        required: &["license", "path"],
+        optional: &["options", "width", "height"],
+        default: "\
+% image options: <%options%>
+\\stepcounter{imagelabel}
+\\centering
+\\addxcontentsline{lof}{section}[]{<%license%>}
+\\adjincludegraphics[max width=<%width%>\\textwidth, max height=<%height%>\\textheight]{<%path%>}\
+",
+    },
+    Snippet {
+        name: "figure_caption",
+        required: &["caption"],
+        optional: &[],
+        default: "\\caption*{<%caption%> (\\arabic{imagelabel})}",
+    },
+    Snippet {
+        name: "figure_inline",
+        required: &["license", "path"],
+        optional: &["options"],
+        default: "
+% image options: <%options%>
+\\stepcounter{imagelabel}
+\\addxcontentsline{lof}{section}[]{<%license%>}
+\\adjincludegraphics[height=\\lineheight]{<%path%>}\
+",
+    },
+    Snippet {
+        name: "internal_href",
+        required: &["url", "caption"],
+        optional: &[],
+        default: "\\href{<%url%>}{\\emph{<%caption%>}}",
+    },
+    // --- HTML Elements ---
+    Snippet {
+        name: "html_italic",
+        required: &["content"],
+        optional: &[],
+        default: "\\textit{<%content%>}",
+    },
+    // --- Lists ---
+    Snippet {
+        name: "item",
+        required: &["content"],
+        optional: &[],
+        default: "\\item <%content%>",
+    },
+    Snippet {
+        name: "item_definition",
+        required: &["content"],
+        optional: &["term"],
+        default: "\\item[<%term%>:] <%content%>",
+    },
+    Snippet {
+        name: "list",
+        required: &["env", "items"],
+        optional: &[],
+        default: "\\begin{<%env%>}
+<%items%>
+\\end{<%env%>}",
+    },
+    // --- Headings ---
+    Snippet {
+        name: "section",
+        required: &["caption"],
+        optional: &["depth"],
+        default: "\\<%depth%>section{<%caption%>}",
+    },
+    // --- Formatting ---
+    Snippet {
+        name: "bold",
+        required: &["content"],
+        optional: &[],
+        default: "\\textbf{<%content%>}",
+    },
+    Snippet {
+        name: "italic",
+        required: &["content"],
+        optional: &[],
+        default: "\\textit{<%content%>}",
+    },
+    Snippet {
+        name: "math",
+        required: &["content"],
+        optional: &[],
+        default: "$<%content%>$",
+    },
+    Snippet {
+        name: "strike_through",
+        required: &["content"],
+        optional: &[],
+        default: "\\sout{<%content%>}",
+    },
+    Snippet {
+        name: "underline",
+        required: &["content"],
+        optional: &[],
+        default: "\\ul{<%content%>}",
+    },
+    Snippet {
+        name: "quote_env",
+        required: &[],
+        optional: &[],
+        default: "displayquote",
+    },
+    // --- Templates ---
+    Snippet {
+        name: "math_env",
+        required: &[],
+        optional: &[],
+        default: "align",
+    },
+    Snippet {
+        name: "important_env",
+        required: &[],
+        optional: &[],
+        default: "important",
+    },
+    Snippet {
+        name: "proof_step_env",
+        required: &[],
+        optional: &[],
+        default: "proofstep",
+    },
+    Snippet {
+        name: "proof_case_env",
+        required: &[],
+        optional: &[],
+        default: "proofcase",
+    },
+    Snippet {
+        name: "induction",
+        required: &[
+            "statement",
+            "base_case",
+            "hypothesis",
+            "step_case_goal",
+            "step_case",
+            "set",
+        ],
+        optional: &[
+            "intro",
+            "intro_end",
+            "base_case_caption",
+            "step_caption",
+            "hypothesis_caption",
+            "step_case_goal_caption",
+            "step_case_caption",
+        ],
+        default: "\\textbf{<%intro%><%set%><%intro_end%>}
+
+<%statement%>
+
+\\begin{enumerate}
+\\item \\textbf{<%base_case_caption%>} <%base_case%>
+\\item \\textbf{<%step_caption%>}
+\\begin{enumerate}
+\\item \\textbf{<%hypothesis_caption%>} <%hypothesis%>
+\\item \\textbf{<%step_case_goal_caption%>} <%step_case_goal%>
+\\item \\textbf{<%step_case_caption%>} <%step_case%>
+\\end{enumerate}
+\\end{enumerate}",
+    },
+    Snippet {
+        name: "induction_set_default",
+        required: &[],
+        optional: &[],
+        default: "$n\\in\\mathcal{N}$",
+    },
+    Snippet {
+        name: "exercise_tasklist",
+        required: &["exercise", "tasks"],
+        optional: &[],
+        default: "<%exercise%>\n\n<%tasks%>",
+    },
+    Snippet {
+        name: "exercise_explanation",
+        required: &["exercise", "explanation"],
+        optional: &[],
+        default: "<%exercise%>\n\n<%explanation%>",
+    },
+    // --- Galleries ---
+    Snippet {
+        name: "gallery_content",
+        required: &["license", "path"],
+        optional: &["options", "width", "height", "caption"],
+        default: "\
+% image options: <%options%>
+\\begin{minipage}[t]{<%width%>\\textwidth}
+    \\centering
+    \\stepcounter{imagelabel}
+    \\addxcontentsline{lof}{section}[]{<%license%>}
+    \\adjincludegraphics[max width=1.\\textwidth,
+        max height=<%height%>\\textheight]{<%path%>}
+    \\captionof*{figure}{<%caption%> (\\arabic{imagelabel})}
+\\end{minipage}
+",
+    },
+    // --- Table ---
+    Snippet {
+        name: "table",
+        required: &["columns", "content"],
+        optional: &["caption"],
+        default: "\\renewcommand{\\arraystretch}{1.5}
+\\begin{longtabu} to \\linewidth {<%columns%>}
+\\caption{<%caption%>}\\\\ \\toprule
+<%content%>
+\\bottomrule
+\\end{longtabu}
+\\renewcommand{\\arraystretch}{1.0}",
+    },
+    Snippet {
+        name: "table_with_header",
+        required: &["header", "body"],
+        optional: &[],
+        default: "<%header%>\\midrule\n<%body%>",
+    },
+    // --- Footnotes ---
+    Snippet {
+        name: "footnote",
+        required: &["content"],
+        optional: &["label"],
+        default: "\\footnote{<%content%>\\label{<%label%>}}",
+    },
+    Snippet {
+        name: "footnote_mark",
+        required: &[],
+        optional: &[],
+        default: "\\footnotemark",
+    },
+    // footnote texts follow their marks, the counter is rewound before them.
+    Snippet {
+        name: "footnote_text",
+        required: &["content"],
+        optional: &["label"],
+        default: "\\stepcounter{footnote}\\footnotetext{<%content%>\\label{<%label%>}}",
+    },
+    Snippet {
+        name: "footnote_rewind",
+        required: &["count"],
+        optional: &[],
+        default: "\\addtocounter{footnote}{-<%count%>}",
+    },
+    Snippet {
+        name: "footnote_ref",
+        required: &["label"],
+        optional: &[],
+        default: "\\footref{<%label%>}",
+    },
+    Snippet {
+        name: "reference_mark",
+        required: &["label"],
+        optional: &[],
+        default: "\\textsuperscript{[<%label%>]}",
+    },
+    Snippet {
+        name: "references",
+        required: &["items"],
+        optional: &[],
+        default: "\\begin{description}
+<%items%>
+\\end{description}",
+    },
+    Snippet {
+        name: "reference_item",
+        required: &["label", "content"],
+        optional: &[],
+        default: "\\item[<%label%>] <%content%>",
+    },
+    // --- Bibliography ---
+    Snippet {
+        name: "citation",
+        required: &["key"],
+        optional: &["pages"],
+        default: "\\cite[<%pages%>]{<%key%>}",
+    },
+    Snippet {
+        name: "article_bibliography",
+        required: &["body"],
+        optional: &[],
+        default: "\\begin{refsection}
+<%body%>
+\\printbibliography[heading=subbibliography]
+\\end{refsection}",
+    },
+    Snippet {
+        name: "bibliography",
+        required: &[],
+        optional: &[],
+        default: "\\printbibliography",
+    },
+    Snippet {
+        name: "bibliography_resource",
+        required: &["path"],
+        optional: &[],
+        default: "\\addbibresource{<%path%>}",
+    },
+    // --- Index ---
+    Snippet {
+        name: "index_entry",
+        required: &["sort", "term"],
+        optional: &[],
+        default: "\\index{<%sort%>@<%term%>}",
+    },
+    Snippet {
+        name: "make_index",
+        required: &[],
+        optional: &[],
+        default: "\\makeindex",
+    },
+    Snippet {
+        name: "print_index",
+        required: &[],
+        optional: &[],
+        default: "\\printindex",
+    },
+    // --- Anchor ---
+    Snippet {
+        name: "label",
+        required: &["label"],
+        optional: &[],
+        default: "\\label{<%label%>}",
+    },
+    Snippet {
+        name: "label_ref",
+        required: &["label", "caption"],
+        optional: &[],
+        default: "\\hyperref[<%label%>]{\\emph{<%caption%>}}",
+    },
+    // --- Main Article ---
+    Snippet {
+        name: "mainarticle",
+        required: &[],
+        optional: &["caption"],
+        default: "$\\rightarrow$ <%caption%>: ",
+    },
+];
+
+/// Former names of snippets, which are still accepted in theme overrides.
+const SNIPPET_ALIASES: &[(&str, &str)] = &[("html_ref", "footnote")];
+
+/// The current name of a snippet, which may be given by a former name.
+fn resolve_alias(name: &str) -> &str {
+    SNIPPET_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, snippet)| *snippet)
+        .unwrap_or(name)
+}
+
+/// Find a snippet by its name.
+pub fn find_snippet(name: &str) -> Option<&'static Snippet> {
+    let name = resolve_alias(name);
+    SNIPPETS.iter().find(|s| s.name == name)
+}
+
+/// Check a definition of the snippet `name` for unknown or missing placeholders.
+pub fn check_snippet(name: &str, source: &str) -> Result<(), String> {
+    let snippet = match find_snippet(name) {
+        Some(snippet) => snippet,
+        None => return Err(format!("unknown latex theme snippet {:?}", name)),
+    };
+    let used = placeholders(source);
+    for placeholder in &used {
+        if !snippet.required.contains(placeholder) && !snippet.optional.contains(placeholder) {
+            return Err(format!(
+                "latex theme snippet {:?} uses unknown placeholder {:?}",
+                name, placeholder
+            ));
+        }
+    }
+    for placeholder in snippet.required {
+        if !used.contains(placeholder) {
+            return Err(format!(
+                "latex theme snippet {:?} lacks required placeholder {:?}",
+                name, placeholder
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The snippets used by the LaTeX renderer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatexTheme {
+    snippets: HashMap<String, String>,
+}
+
+impl LatexTheme {
+    /// Fill in the placeholders of a snippet.
+    pub fn render(&self, name: &str, values: &[(&str, &str)]) -> String {
+        let source = self
+            .snippets
+            .get(name)
+            .unwrap_or_else(|| panic!("latex theme has no snippet {:?}!", name));
+        let values: HashMap<&str, String> =
+            values.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        fill_placeholders(source, &values).unwrap_or_else(|missing| {
+            panic!(
+                "no values for placeholders {:?} of latex theme snippet {:?}!",
+                missing, name
+            )
+        })
+    }
+
+    /// Source of a snippet without placeholders, like an environment name.
+    pub fn get(&self, name: &str) -> &str {
+        self.snippets
+            .get(name)
+            .map(|s| s.as_str())
+            .unwrap_or_else(|| panic!("latex theme has no snippet {:?}!", name))
+    }
+}
+
+impl Default for LatexTheme {
+    fn default() -> LatexTheme {
+        LatexTheme {
+            snippets: SNIPPETS
+                .iter()
+                .map(|s| (s.name.to_string(), s.default.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl Serialize for LatexTheme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snippets.serialize(serializer)
+    }
+}
+
+/// Only the overridden snippets need to be given, all others keep their default.
+impl<'de> Deserialize<'de> for LatexTheme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<LatexTheme, D::Error> {
+        let overrides = HashMap::<String, String>::deserialize(deserializer)?;
+        let mut theme = LatexTheme::default();
+        for (name, source) in overrides {
+            check_snippet(&name, &source).map_err(D::Error::custom)?;
+            theme
+                .snippets
+                .insert(resolve_alias(&name).to_string(), source);
+        }
+        Ok(theme)
+    }
+}
@@ -0,0 +1,178 @@
+//! Implements the `latex-log` target.
+//!
+//! This target reads the log of a pdflatex or lualatex run and reports errors,
+//! overfull boxes, undefined references and citations. With the source map
+//! written by the `latex` target, the problems are reported at their article
+//! and position in the wiki source, so authors can fix them without knowing
+//! LaTeX. Problems in packages or other included files are reported at the
+//! file given by the log.
+
+use crate::diagnostics::{has_errors, write_diagnostics, Diagnostic, Severity};
+use crate::inputs::record_input;
+use crate::latex::SourceMapEntry;
+use crate::preamble::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+
+mod parser;
+
+pub use self::parser::{parse_log, LogProblem, LogProblemKind};
+
+#[derive(Debug, StructOpt)]
+pub struct LatexLogArgs {
+    /// Path to the log file of the LaTeX run.
+    #[structopt(parse(from_os_str))]
+    log_path: PathBuf,
+
+    /// Path to the source map written by the `latex` target.
+    #[structopt(parse(from_os_str), short = "m", long = "source-map")]
+    source_map: Option<PathBuf>,
+
+    /// Write a JSON report of all problems to this file.
+    #[structopt(parse(from_os_str), short = "r", long = "report")]
+    report_path: Option<PathBuf>,
+}
+
+/// Maps problems in a LaTeX log back to the wiki source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatexLogTarget {
+    /// Overfull boxes below this amount (in pt) are not reported.
+    overfull_threshold: f32,
+    /// Exit with an error if the log contains errors.
+    fail_on_error: bool,
+}
+
+impl Default for LatexLogTarget {
+    fn default() -> LatexLogTarget {
+        LatexLogTarget {
+            overfull_threshold: 1.0,
+            fail_on_error: false,
+        }
+    }
+}
+
+/// Find the source map entry of a line of the LaTeX source.
+fn find_origin(map: &[SourceMapEntry], line: usize) -> Option<&SourceMapEntry> {
+    map.iter()
+        .find(|e| e.start_line <= line && line <= e.end_line)
+}
+
+impl LatexLogTarget {
+    fn to_diagnostic(
+        &self,
+        problem: &LogProblem,
+        map: &[SourceMapEntry],
+        tex_name: &str,
+    ) -> Diagnostic {
+        let (severity, kind, message) = match problem.kind {
+            LogProblemKind::Error => (
+                Severity::Error,
+                "latex-error",
+                format!("LaTeX error: {}", &problem.message),
+            ),
+            LogProblemKind::OverfullBox => (
+                Severity::Warning,
+                "overfull-box",
+                format!(
+                    "content sticks out of the page by {}pt, \
+                     consider shortening formulas or long words: {}",
+                    problem.overfull_pt.unwrap_or_default(),
+                    &problem.message
+                ),
+            ),
+            LogProblemKind::UndefinedReference => (
+                Severity::Warning,
+                "undefined-reference",
+                format!("link target not found in the book: {}", &problem.message),
+            ),
+            LogProblemKind::UndefinedCitation => (
+                Severity::Warning,
+                "undefined-citation",
+                format!(
+                    "cited work not found in the bibliography: {}",
+                    &problem.message
+                ),
+            ),
+        };
+
+        // the source map only covers the main LaTeX file, not included files.
+        let file = problem
+            .file
+            .as_ref()
+            .map(|f| f.as_str())
+            .unwrap_or(tex_name);
+        let in_main_file = Path::new(file).file_name() == Path::new(tex_name).file_name();
+        let origin = problem
+            .line
+            .filter(|_| in_main_file)
+            .and_then(|line| find_origin(map, line));
+
+        match origin {
+            Some(origin) => {
+                Diagnostic::new(severity, kind, message, &origin.document, &origin.position)
+            }
+            // without origin, point to the LaTeX source itself.
+            None => {
+                let position = Position {
+                    offset: 0,
+                    line: problem.line.unwrap_or(0),
+                    col: 0,
+                };
+                let span = Span {
+                    start: position.clone(),
+                    end: position,
+                };
+                Diagnostic::new(severity, kind, message, file, &span)
+            }
+        }
+    }
+}
+
+impl<'a> Target<&'a LatexLogArgs, ()> for LatexLogTarget {
+    fn target_type(&self) -> TargetType {
+        TargetType::LatexLog
+    }
+    fn export(
+        &self,
+        _: &Element,
+        _: (),
+        args: &'a LatexLogArgs,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        record_input(&args.log_path);
+        let log = String::from_utf8_lossy(&fs::read(&args.log_path)?).to_string();
+
+        let map: Vec<SourceMapEntry> = match args.source_map {
+            Some(ref path) => {
+                record_input(path);
+                let file = fs::File::open(path)?;
+                serde_json::from_reader(&file).expect("error reading source map:")
+            }
+            None => vec![],
+        };
+
+        let tex_name = args.log_path.with_extension("tex");
+        let tex_name = tex_name.to_string_lossy();
+        let diagnostics: Vec<_> = parse_log(&log)
+            .iter()
+            .filter(|p| p.overfull_pt.unwrap_or(self.overfull_threshold) >= self.overfull_threshold)
+            .map(|p| self.to_diagnostic(p, &map, &tex_name))
+            .collect();
+
+        write_diagnostics(&diagnostics, out)?;
+        if let Some(ref path) = args.report_path {
+            let file = fs::File::create(path)?;
+            serde_json::to_writer(&file, &diagnostics)
+                .expect("could not serialize latex log report!");
+        }
+        if self.fail_on_error && has_errors(&diagnostics) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "the LaTeX log contains errors!",
+            ));
+        }
+        Ok(())
+    }
+}
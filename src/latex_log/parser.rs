@@ -0,0 +1,178 @@
+//! Extraction of problems from pdflatex and lualatex log files.
+
+use crate::preamble::*;
+
+/// TeX wraps log lines at this many characters (`max_print_line`).
+const LOG_LINE_WIDTH: usize = 79;
+
+/// Number of lines after an error message searched for its line number.
+const ERROR_CONTEXT_LINES: usize = 10;
+
+/// Kind of a problem reported by LaTeX.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogProblemKind {
+    Error,
+    OverfullBox,
+    UndefinedReference,
+    UndefinedCitation,
+}
+
+/// A problem found in the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogProblem {
+    pub kind: LogProblemKind,
+    pub message: String,
+    /// File the problem occurred in, if known from the log.
+    pub file: Option<String>,
+    /// Line in that file, if given by the log.
+    pub line: Option<usize>,
+    /// How much an overfull box is too wide or high, in pt.
+    pub overfull_pt: Option<f32>,
+}
+
+/// Join a log line with its continuations, if TeX wrapped it.
+/// Returns the logical line and the number of physical lines used.
+fn logical_line(lines: &[&str]) -> (String, usize) {
+    let mut result = String::new();
+    let mut count = 0;
+    for line in lines {
+        result.push_str(line);
+        count += 1;
+        if line.chars().count() != LOG_LINE_WIDTH {
+            break;
+        }
+    }
+    (result, count)
+}
+
+/// Parse the number directly following `marker` in `text`.
+fn number_after(text: &str, marker: &str) -> Option<usize> {
+    let start = text.find(marker)? + marker.len();
+    let digits: String = text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// Split an error in `-file-line-error` format, like `./doc.tex:12: message`.
+fn file_line_error(line: &str) -> Option<(&str, usize, &str)> {
+    if !(line.starts_with("./") || line.starts_with('/')) {
+        return None;
+    }
+    let mut parts = line.splitn(3, ':');
+    let file = parts.next()?;
+    let number = parts.next()?.parse().ok()?;
+    let message = parts.next()?;
+    if !message.starts_with(' ') {
+        return None;
+    }
+    Some((file, number, message.trim()))
+}
+
+/// Files TeX is reading while writing the log.
+///
+/// TeX prints `(` and the file name when it opens a file and `)` when it closes
+/// it. Other parentheses in the log are tracked as well, so they stay balanced.
+#[derive(Default)]
+struct FileStack {
+    files: Vec<Option<String>>,
+}
+
+impl FileStack {
+    fn update(&mut self, line: &str) {
+        for (index, c) in line.char_indices() {
+            match c {
+                '(' => {
+                    let name: String = line[index + 1..]
+                        .chars()
+                        .take_while(|c| !c.is_whitespace() && *c != '(' && *c != ')')
+                        .collect();
+                    let is_file = name.starts_with("./")
+                        || name.starts_with('/')
+                        || (name.contains('.') && name.chars().any(|c| c.is_alphabetic()));
+                    self.files.push(if is_file { Some(name) } else { None });
+                }
+                ')' => {
+                    self.files.pop();
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// The innermost open file.
+    fn current(&self) -> Option<String> {
+        self.files.iter().rev().find_map(|f| f.clone())
+    }
+}
+
+/// Amount of an overfull box, like `12.3` in `Overfull \hbox (12.3pt too wide)`.
+fn overfull_amount(line: &str) -> Option<f32> {
+    let start = line.find('(')? + 1;
+    let end = line[start..].find("pt")? + start;
+    line[start..end].parse().ok()
+}
+
+/// Extract errors, overfull boxes and undefined references from a log.
+pub fn parse_log(log: &str) -> Vec<LogProblem> {
+    let lines: Vec<&str> = log.lines().collect();
+    let mut problems = vec![];
+    let mut files = FileStack::default();
+    let mut index = 0;
+    while index < lines.len() {
+        let (line, used) = logical_line(&lines[index..]);
+        let following = &lines[index + used..];
+        let file = files.current();
+
+        if line.starts_with("! ") {
+            let number = following
+                .iter()
+                .take(ERROR_CONTEXT_LINES)
+                .find(|l| l.starts_with("l."))
+                .and_then(|l| number_after(l, "l."));
+            problems.push(LogProblem {
+                kind: LogProblemKind::Error,
+                message: line[2..].trim().to_string(),
+                file,
+                line: number,
+                overfull_pt: None,
+            });
+        } else if let Some((path, number, message)) = file_line_error(&line) {
+            problems.push(LogProblem {
+                kind: LogProblemKind::Error,
+                message: message.to_string(),
+                file: Some(path.to_string()),
+                line: Some(number),
+                overfull_pt: None,
+            });
+        } else if line.starts_with("Overfull \\") {
+            problems.push(LogProblem {
+                kind: LogProblemKind::OverfullBox,
+                message: line.trim().to_string(),
+                file,
+                line: number_after(&line, "at lines ").or_else(|| number_after(&line, "at line ")),
+                overfull_pt: overfull_amount(&line),
+            });
+        } else if line.contains("Warning: Reference") || line.contains("Warning: Citation") {
+            let kind = if line.contains("Warning: Citation") {
+                LogProblemKind::UndefinedCitation
+            } else {
+                LogProblemKind::UndefinedReference
+            };
+            let message = trim_prefix(line.trim(), "latex warning: ");
+            problems.push(LogProblem {
+                kind,
+                message: message.to_string(),
+                file,
+                line: number_after(&line, "input line "),
+                overfull_pt: None,
+            });
+        } else if !line.starts_with("l.") {
+            files.update(&line);
+        }
+        index += used;
+    }
+    problems
+}
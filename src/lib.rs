@@ -4,14 +4,24 @@ mod util;
 #[macro_use]
 mod settings;
 mod anchors;
+mod audit;
+mod bibliography;
 mod compose;
 mod deps;
+mod diagnostics;
+mod footnotes;
 mod html;
+mod inputs;
+mod labels;
 mod latex;
+mod latex_log;
+mod links;
+mod lint;
 mod normalize;
 mod pdf;
 mod sections;
 mod stats;
+mod title;
 mod transformations;
 
 #[cfg(test)]
@@ -35,10 +45,17 @@ use std::io;
 
 // public exports
 pub use crate::anchors::{AnchorsArgs, AnchorsTarget};
+pub use crate::audit::{AccessibilityArgs, AccessibilityTarget, LinkAuditArgs, LinkAuditTarget};
+pub use crate::bibliography::{BibliographyArgs, BibliographyTarget};
 pub use crate::compose::{ComposeArgs, ComposeTarget};
-pub use crate::deps::{MediaDepArgs, MediaDepTarget, SectionDepArgs, SectionDepTarget};
+pub use crate::deps::{DepFormat, MediaDepArgs, MediaDepTarget, SectionDepArgs, SectionDepTarget};
+pub use crate::diagnostics::{Diagnostic, Severity};
 pub use crate::html::{HTMLArgs, HTMLTarget};
+pub use crate::inputs::{record_input, recorded_inputs, write_inputs};
 pub use crate::latex::{LatexArgs, LatexTarget};
+pub use crate::latex_log::{LatexLogArgs, LatexLogTarget};
+pub use crate::links::{LinksArgs, LinksTarget};
+pub use crate::lint::{LintArgs, LintTarget};
 pub use crate::normalize::{NormalizeArgs, NormalizeTarget};
 pub use crate::pdf::{PDFArgs, PDFTarget};
 pub use crate::sections::{SectionsArgs, SectionsTarget};
@@ -68,7 +85,13 @@ pub enum TargetType {
     Normalize,
     Compose,
     Anchors,
+    Links,
+    Lint,
+    LinkAudit,
+    Accessibility,
     Latex,
+    LatexLog,
+    Bibliography,
     #[serde(rename = "pdf")]
     PDF,
     Stats,
@@ -85,7 +108,13 @@ pub enum Targets {
     Normalize(NormalizeTarget),
     Compose(ComposeTarget),
     Anchors(AnchorsTarget),
+    Links(LinksTarget),
+    Lint(LintTarget),
+    LinkAudit(LinkAuditTarget),
+    Accessibility(AccessibilityTarget),
     Latex(LatexTarget),
+    LatexLog(LatexLogTarget),
+    Bibliography(BibliographyTarget),
     PDF(PDFTarget),
     Stats(StatsTarget),
     HTML(HTMLTarget),
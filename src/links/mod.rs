@@ -0,0 +1,205 @@
+//! Implements the `links` target.
+//!
+//! The `links` target merges the reference lists produced by the `stats` target
+//! for every article of a book. It writes a reverse index (which articles link to
+//! an anchor), lists anchors nobody links to and optionally writes a graph
+//! of links between the articles of the book.
+
+use crate::anchors::{AnchorIndex, AnchorKind};
+use crate::preamble::*;
+use crate::title;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct LinksArgs {
+    /// Path to a list of link targets (anchors) available in the export.
+    #[structopt(parse(try_from_str = "load_anchor_index"))]
+    available_anchors: AnchorIndex,
+
+    /// Output files of the `stats` target, one per article.
+    #[structopt(parse(from_os_str))]
+    stats_files: Vec<PathBuf>,
+
+    /// Write the graph of links between articles to this file.
+    #[structopt(parse(from_os_str), short = "g", long = "graph")]
+    graph_path: Option<PathBuf>,
+}
+
+/// Output format of the article graph.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphFormat {
+    /// A graphviz digraph.
+    Dot,
+    /// A JSON list of edges.
+    Json,
+}
+
+impl Default for GraphFormat {
+    fn default() -> GraphFormat {
+        GraphFormat::Dot
+    }
+}
+
+/// Builds a reverse link index from article stats.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinksTarget {
+    /// Output format of the article graph.
+    graph_format: GraphFormat,
+    /// Consider document anchors as orphaned if no other article links to them.
+    orphaned_documents: bool,
+}
+
+/// The part of the `stats` output needed to build the index.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ArticleLinks {
+    document_title: String,
+    reference_targets: Vec<String>,
+}
+
+/// A link between two articles of the book.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArticleLink {
+    /// Anchor of the linking article.
+    pub source: String,
+    /// Anchor of the linked article.
+    pub target: String,
+    /// Number of distinct references from `source` to anchors in `target`.
+    pub count: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct LinkIndex {
+    /// anchor -> articles linking to it
+    references: BTreeMap<String, BTreeSet<String>>,
+    /// anchors of the export nobody links to
+    orphaned_anchors: Vec<String>,
+}
+
+impl<'a> Target<&'a LinksArgs, ()> for LinksTarget {
+    fn target_type(&self) -> TargetType {
+        TargetType::Links
+    }
+    /// The input document is ignored, only the stats files are read.
+    fn export<'e>(
+        &self,
+        _root: &'e Element,
+        _: (),
+        args: &'a LinksArgs,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        let mut articles = vec![];
+        for path in &args.stats_files {
+            record_input(path);
+            let file = fs::File::open(path)?;
+            let links: ArticleLinks = serde_json::from_reader(&file)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            articles.push(links);
+        }
+
+        let anchors = &args.available_anchors;
+        let mut index = LinkIndex::default();
+        let mut edges: BTreeMap<(String, String), usize> = BTreeMap::new();
+
+        for article in &articles {
+            let source = title::anchor(&article.document_title, None);
+            for target in &article.reference_targets {
+                let anchor = match matching_anchor(target, &article.document_title, anchors) {
+                    Some(anchor) => anchor,
+                    None => continue,
+                };
+                index
+                    .references
+                    .entry(anchor.clone())
+                    .or_default()
+                    .insert(source.clone());
+
+                let target_article = title::split_target(&anchor).0.to_string();
+                if target_article != source && anchors.contains(&target_article) {
+                    *edges.entry((source.clone(), target_article)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut orphaned: Vec<_> = anchors
+            .anchors()
+            .filter(|a| !index.references.contains_key(*a))
+            .filter(|a| self.orphaned_documents || !is_document_anchor(anchors, a))
+            .cloned()
+            .collect();
+        orphaned.sort();
+        index.orphaned_anchors = orphaned;
+
+        if let Some(ref path) = args.graph_path {
+            let edges: Vec<_> = edges
+                .into_iter()
+                .map(|((source, target), count)| ArticleLink {
+                    source,
+                    target,
+                    count,
+                })
+                .collect();
+            let mut file = fs::File::create(path)?;
+            write_graph(self.graph_format, &edges, &mut file)?;
+        }
+
+        writeln!(
+            out,
+            "{}",
+            serde_json::to_string(&index).expect("could not serialize the link index!")
+        )
+    }
+}
+
+fn is_document_anchor(anchors: &AnchorIndex, anchor: &str) -> bool {
+    match anchors.entry(anchor) {
+        Some(entry) => entry.kind == AnchorKind::Document,
+        None => title::split_target(anchor).1.is_none(),
+    }
+}
+
+/// Quote a DOT identifier, escaping `"` and `\\`.
+pub fn dot_string(input: &str) -> String {
+    let mut result = String::from("\"");
+    for c in input.chars() {
+        if c == '"' || c == '\\' {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result.push('"');
+    result
+}
+
+/// Write the article graph in the given format.
+pub fn write_graph(
+    format: GraphFormat,
+    edges: &[ArticleLink],
+    out: &mut io::Write,
+) -> io::Result<()> {
+    match format {
+        GraphFormat::Json => writeln!(
+            out,
+            "{}",
+            serde_json::to_string(edges).expect("could not serialize the article graph!")
+        ),
+        GraphFormat::Dot => {
+            writeln!(out, "digraph links {{")?;
+            for edge in edges {
+                writeln!(
+                    out,
+                    "    {} -> {} [weight={}];",
+                    dot_string(&edge.source),
+                    dot_string(&edge.target),
+                    edge.count
+                )?;
+            }
+            writeln!(out, "}}")
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Implements the `lint` target.
+//!
+//! The `lint` target checks a document against a set of rules (see `rules.rs`).
+//! Rules can be disabled or given a different severity in the configuration.
+//! If any error is found, the program exits with a non-zero exit code.
+
+use crate::diagnostics::{has_errors, write_diagnostics, Diagnostic, Severity};
+use crate::preamble::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+mod rules;
+mod templates;
+
+use self::rules::{find_rule, LintContext, RULES};
+
+#[derive(Debug, StructOpt)]
+pub struct LintArgs {
+    /// Title of the document beeing processed.
+    document_title: String,
+
+    /// Write a JSON report of all problems found to this file.
+    #[structopt(parse(from_os_str), short = "r", long = "report")]
+    report_path: Option<PathBuf>,
+
+    /// Only print the available rules.
+    #[structopt(short = "l", long = "list-rules")]
+    list_rules: bool,
+}
+
+/// Configuration of a lint rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleConfig {
+    /// Run this rule?
+    pub enabled: bool,
+    /// Severity of the problems found by this rule.
+    pub severity: Option<Severity>,
+}
+
+impl Default for RuleConfig {
+    fn default() -> RuleConfig {
+        RuleConfig {
+            enabled: true,
+            severity: None,
+        }
+    }
+}
+
+/// Checks the document for common problems.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintTarget {
+    /// Configuration of the lint rules by name.
+    /// Rules which are not listed run with their default severity.
+    rules: HashMap<String, RuleConfig>,
+    /// Maximum nesting depth of lists.
+    max_list_depth: usize,
+}
+
+impl Default for LintTarget {
+    fn default() -> LintTarget {
+        LintTarget {
+            rules: RULES
+                .iter()
+                .map(|r| {
+                    let config = RuleConfig {
+                        enabled: true,
+                        severity: Some(r.severity),
+                    };
+                    (r.name.to_string(), config)
+                })
+                .collect(),
+            max_list_depth: 3,
+        }
+    }
+}
+
+impl LintTarget {
+    /// A configuration which runs only the given rules with their default severity.
+    pub fn only(names: &[&str]) -> LintTarget {
+        LintTarget {
+            rules: RULES
+                .iter()
+                .map(|r| {
+                    let config = RuleConfig {
+                        enabled: names.contains(&r.name),
+                        severity: Some(r.severity),
+                    };
+                    (r.name.to_string(), config)
+                })
+                .collect(),
+            ..LintTarget::default()
+        }
+    }
+
+    /// Check a document with all enabled rules.
+    pub fn lint(
+        &self,
+        root: &Element,
+        settings: &Settings,
+        document: &str,
+    ) -> io::Result<Vec<Diagnostic>> {
+        let mut linter = Linter {
+            path: vec![],
+            document: document.to_string(),
+            diagnostics: vec![],
+        };
+        linter.run(root, (settings, self), &mut vec![])?;
+        Ok(linter.diagnostics)
+    }
+
+    /// The severity of a rule, if it is enabled.
+    fn rule_severity(&self, name: &str, default: Severity) -> Option<Severity> {
+        match self.rules.get(name) {
+            Some(config) if !config.enabled => None,
+            Some(config) => Some(config.severity.unwrap_or(default)),
+            None => Some(default),
+        }
+    }
+}
+
+/// Runs all enabled rules on every element.
+struct Linter<'e> {
+    path: Vec<&'e Element>,
+    /// Name of the document, used in diagnostics.
+    document: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'e, 's: 'e, 't: 'e> Traversion<'e, (&'s Settings, &'t LintTarget)> for Linter<'e> {
+    path_methods!('e);
+
+    fn work(
+        &mut self,
+        root: &'e Element,
+        params: (&'s Settings, &'t LintTarget),
+        _: &mut io::Write,
+    ) -> io::Result<bool> {
+        let (settings, target) = params;
+        // the current element is the last path element
+        let context = LintContext {
+            parents: &self.path[..self.path.len().saturating_sub(1)],
+            settings,
+            target,
+        };
+        for rule in RULES {
+            let severity = match target.rule_severity(rule.name, rule.severity) {
+                Some(severity) => severity,
+                None => continue,
+            };
+            if let Some(message) = (rule.check)(root, &context) {
+                self.diagnostics.push(Diagnostic::new(
+                    severity,
+                    rule.name,
+                    message,
+                    &self.document,
+                    root.get_position(),
+                ));
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<'a, 's> Target<&'a LintArgs, &'s Settings> for LintTarget {
+    fn target_type(&self) -> TargetType {
+        TargetType::Lint
+    }
+    fn export(
+        &self,
+        root: &Element,
+        settings: &'s Settings,
+        args: &'a LintArgs,
+        out: &mut io::Write,
+    ) -> io::Result<()> {
+        if args.list_rules {
+            for rule in RULES {
+                let severity = self.rule_severity(rule.name, rule.severity);
+                let severity = severity
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "disabled".into());
+                writeln!(out, "{} ({}): {}", rule.name, severity, rule.description)?;
+            }
+            return Ok(());
+        }
+
+        for name in self.rules.keys() {
+            if find_rule(name).is_none() {
+                panic!("unknown lint rule \"{}\" in configuration!", name);
+            }
+        }
+
+        let diagnostics = self.lint(root, settings, &args.document_title)?;
+
+        write_diagnostics(&diagnostics, out)?;
+        if let Some(ref path) = args.report_path {
+            let file = fs::File::create(path)?;
+            serde_json::to_writer(&file, &diagnostics).expect("could not serialize lint report!");
+        }
+        if has_errors(&diagnostics) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the document violates lint rules!",
+            ));
+        }
+        Ok(())
+    }
+}
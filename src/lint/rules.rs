@@ -0,0 +1,186 @@
+//! The lint rule registry.
+//!
+//! Every rule is a check function which is called for each element of the
+//! document and returns a message if the element violates the rule.
+
+use super::templates::template_spec;
+use super::LintTarget;
+use crate::diagnostics::Severity;
+use crate::preamble::*;
+
+/// Information available to a rule check.
+pub struct LintContext<'a, 'e: 'a> {
+    /// Ancestors of the checked element, outermost first.
+    pub parents: &'a [&'e Element],
+    pub settings: &'a Settings,
+    pub target: &'a LintTarget,
+}
+
+/// A registered lint rule.
+pub struct LintRule {
+    /// Identifier of the rule, used in the configuration and in diagnostics.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Severity if not configured otherwise.
+    pub severity: Severity,
+    pub check: fn(&Element, &LintContext) -> Option<String>,
+}
+
+/// All available lint rules.
+pub const RULES: &[LintRule] = &[
+    LintRule {
+        name: "empty-heading",
+        description: "headings must have a caption.",
+        severity: Severity::Error,
+        check: empty_heading,
+    },
+    LintRule {
+        name: "image-without-caption",
+        description: "embedded images should have a caption.",
+        severity: Severity::Warning,
+        check: image_without_caption,
+    },
+    LintRule {
+        name: "formula-in-text",
+        description: "formulas should be written in <math> tags, not in plain text.",
+        severity: Severity::Warning,
+        check: formula_in_text,
+    },
+    LintRule {
+        name: "bold-heading",
+        description: "a paragraph with only bold text should be a heading.",
+        severity: Severity::Warning,
+        check: bold_heading,
+    },
+    LintRule {
+        name: "nested-list",
+        description: "lists should not be nested deeper than `max_list_depth`.",
+        severity: Severity::Warning,
+        check: nested_list,
+    },
+    LintRule {
+        name: "template-spec",
+        description: "templates must match the template specification.",
+        severity: Severity::Error,
+        check: template_spec,
+    },
+];
+
+/// Find a rule by its name.
+pub fn find_rule(name: &str) -> Option<&'static LintRule> {
+    RULES.iter().find(|r| r.name == name)
+}
+
+fn empty_heading(root: &Element, _: &LintContext) -> Option<String> {
+    match root {
+        Element::Heading(ref heading) if extract_plain_text(&heading.caption).trim().is_empty() => {
+            Some("heading without caption.".into())
+        }
+        _ => None,
+    }
+}
+
+fn image_without_caption(root: &Element, context: &LintContext) -> Option<String> {
+    match root {
+        Element::InternalReference(ref iref)
+            if is_file(iref, context.settings)
+                && extract_plain_text(&iref.caption).trim().is_empty() =>
+        {
+            Some(format!(
+                "image {:?} has no caption.",
+                extract_plain_text(&iref.target).trim()
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Does the text look like it contains LaTeX markup?
+fn looks_like_formula(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    let command = chars
+        .windows(2)
+        .any(|w| w[0] == '\\' && w[1].is_ascii_alphabetic());
+    let dollars = text.matches('$').count() >= 2;
+    // exponents like `x^2` or `e^{x}`
+    let exponent = chars.windows(3).any(|w| {
+        (w[0].is_alphanumeric() || w[0] == ')' || w[0] == '}')
+            && w[1] == '^'
+            && (w[2].is_alphanumeric() || w[2] == '{' || w[2] == '(')
+    });
+    command || dollars || exponent
+}
+
+fn formula_in_text(root: &Element, context: &LintContext) -> Option<String> {
+    let text = match root {
+        Element::Text(ref text) => &text.text,
+        _ => return None,
+    };
+    let in_formula = context.parents.iter().any(|e| match e {
+        Element::Formatted(Formatted {
+            markup: MarkupType::Math,
+            ..
+        })
+        | Element::Formatted(Formatted {
+            markup: MarkupType::NoWiki,
+            ..
+        }) => true,
+        Element::HtmlTag(ref tag) => {
+            let name = tag.name.trim().to_lowercase();
+            name == "math" || name == "code" || name == "pre" || name == "syntaxhighlight"
+        }
+        _ => false,
+    });
+    if !in_formula && looks_like_formula(text) {
+        Some(format!(
+            "{:?} looks like a formula outside of <math>.",
+            text.trim()
+        ))
+    } else {
+        None
+    }
+}
+
+fn bold_heading(root: &Element, _: &LintContext) -> Option<String> {
+    let paragraph = match root {
+        Element::Paragraph(ref paragraph) => paragraph,
+        _ => return None,
+    };
+    let mut content = paragraph.content.iter().filter(|e| match e {
+        Element::Text(ref text) => !text.text.trim().is_empty(),
+        _ => true,
+    });
+    match (content.next(), content.next()) {
+        (
+            Some(Element::Formatted(Formatted {
+                markup: MarkupType::Bold,
+                ..
+            })),
+            None,
+        ) => Some("paragraph with only bold text, use a heading instead.".into()),
+        _ => None,
+    }
+}
+
+fn nested_list(root: &Element, context: &LintContext) -> Option<String> {
+    if let Element::List(_) = root {
+        let depth = 1 + context
+            .parents
+            .iter()
+            .filter(|e| {
+                if let Element::List(_) = e {
+                    true
+                } else {
+                    false
+                }
+            })
+            .count();
+        if depth > context.target.max_list_depth {
+            return Some(format!(
+                "list nested {} levels deep, at most {} are allowed.",
+                depth, context.target.max_list_depth
+            ));
+        }
+    }
+    None
+}
@@ -0,0 +1,134 @@
+//! The `template-spec` rule: explains why templates do not match the
+//! MFNF template specification.
+
+use super::rules::LintContext;
+use crate::preamble::*;
+use mfnf_template_spec::{parse_template, spec, Priority};
+
+/// Maximum edit distance of a name suggestion.
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+/// Find the name closest to `name`, if it is similar enough.
+fn suggest<'n, I: Iterator<Item = &'n str>>(name: &str, names: I) -> Option<&'n str> {
+    names
+        .map(|n| (edit_distance(name, n), n))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min()
+        .map(|(_, n)| n)
+}
+
+fn with_suggestion(message: String, suggestion: Option<&str>) -> String {
+    match suggestion {
+        Some(suggestion) => format!("{} Did you mean {:?}?", message, suggestion),
+        None => message,
+    }
+}
+
+/// Name of a template which should match the specification, but does not.
+fn invalid_template_name(template: &Template) -> Option<String> {
+    let name = extract_plain_text(&template.name).trim().to_lowercase();
+    // parser functions like section inclusions are not templates.
+    if name.starts_with('#') || is_index_template(template) || parse_template(template).is_some() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Argument names are matched like template names, ignoring case and whitespace.
+fn argument_name(argument: &TemplateArgument) -> String {
+    argument.name.trim().to_lowercase()
+}
+
+/// Reports unknown templates and missing arguments at the template,
+/// problems with an argument at the argument itself.
+pub fn template_spec(root: &Element, context: &LintContext) -> Option<String> {
+    match root {
+        Element::Template(ref template) => check_template(template),
+        Element::TemplateArgument(ref argument) => match context.parents.last() {
+            Some(Element::Template(ref template)) => check_argument(template, argument),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn check_template(template: &Template) -> Option<String> {
+    let name = invalid_template_name(template)?;
+    let specs = spec();
+    let template_spec = match specs
+        .iter()
+        .find(|s| s.names.iter().any(|n| n.trim().to_lowercase() == name))
+    {
+        Some(template_spec) => template_spec,
+        None => {
+            let names = specs.iter().flat_map(|s| s.names.iter().map(|n| n.trim()));
+            let message = format!("unknown template {:?}.", &name);
+            return Some(with_suggestion(message, suggest(&name, names)));
+        }
+    };
+
+    let mut messages = vec![];
+    for attribute in &template_spec.attributes {
+        let required = if let Priority::Required = attribute.priority {
+            true
+        } else {
+            false
+        };
+        let present = template.content.iter().any(|argument| match argument {
+            Element::TemplateArgument(ref argument) => attribute
+                .names
+                .iter()
+                .any(|n| n.trim().to_lowercase() == argument_name(argument)),
+            _ => false,
+        });
+        if required && !present {
+            messages.push(format!(
+                "required argument {:?} of {:?} is missing.",
+                &attribute.default_name, &name
+            ));
+        }
+    }
+    if messages.is_empty() {
+        None
+    } else {
+        Some(messages.join(" "))
+    }
+}
+
+fn check_argument(template: &Template, argument: &TemplateArgument) -> Option<String> {
+    let name = invalid_template_name(template)?;
+    let specs = spec();
+    let template_spec = specs
+        .iter()
+        .find(|s| s.names.iter().any(|n| n.trim().to_lowercase() == name))?;
+
+    let attribute = template_spec.attributes.iter().find(|a| {
+        a.names
+            .iter()
+            .any(|n| n.trim().to_lowercase() == argument_name(argument))
+    });
+    match attribute {
+        Some(attribute) => {
+            if (attribute.predicate)(&argument.value).is_err() {
+                Some(format!(
+                    "argument {:?} of {:?} has invalid content, expected {}.",
+                    &argument.name, &name, &attribute.predicate_name
+                ))
+            } else {
+                None
+            }
+        }
+        None => {
+            let names = template_spec
+                .attributes
+                .iter()
+                .flat_map(|a| a.names.iter().map(|n| n.trim()));
+            let message = format!("unknown argument {:?} of {:?}.", &argument.name, &name);
+            Some(with_suggestion(
+                message,
+                suggest(&argument_name(argument), names),
+            ))
+        }
+    }
+}
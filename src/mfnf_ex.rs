@@ -9,7 +9,7 @@ extern crate serde_json;
 extern crate serde_yaml;
 extern crate structopt;
 
-use mediawiki_parser::Element;
+use mediawiki_parser::{Document, Element, Span};
 use std::fs;
 use std::io;
 use std::path::PathBuf;
@@ -33,6 +33,9 @@ struct Args {
     /// Path to the media file directory.
     #[structopt(parse(from_os_str), short = "e", long = "media-path")]
     media_path: Option<PathBuf>,
+    /// Write the files read in this run to this path, one per line.
+    #[structopt(parse(from_os_str), long = "inputs-file")]
+    inputs_file: Option<PathBuf>,
 
     /// The target configuration (subtarget) to use. e.g. `default` or `print`.
     configuration: String,
@@ -68,10 +71,34 @@ enum Commands {
         about = "export a list of anchor targets for this document."
     )]
     Anchors(AnchorsArgs),
+    #[structopt(name = "links", about = "merge article stats to a reverse link index.")]
+    Links(LinksArgs),
+    #[structopt(name = "lint", about = "check the document for common problems.")]
+    Lint(LintArgs),
+    #[structopt(
+        name = "link-audit",
+        about = "check the external links of the document."
+    )]
+    LinkAudit(LinkAuditArgs),
+    #[structopt(
+        name = "accessibility",
+        about = "check media, tables and formatting for accessibility."
+    )]
+    Accessibility(AccessibilityArgs),
     #[structopt(name = "html", about = "export the document as html.")]
     HTML(HTMLArgs),
     #[structopt(name = "latex", about = "export the document as latex.")]
     Latex(LatexArgs),
+    #[structopt(
+        name = "latex-log",
+        about = "map problems in a latex log back to the article."
+    )]
+    LatexLog(LatexLogArgs),
+    #[structopt(
+        name = "bibliography",
+        about = "merge the works cited in articles to a biblatex file."
+    )]
+    Bibliography(BibliographyArgs),
     #[structopt(name = "pdf", about = "export pdf options for the document.")]
     PDF(PDFArgs),
     #[structopt(name = "stats", about = "export document statistics.")]
@@ -104,6 +131,7 @@ fn main() -> Result<(), std::io::Error> {
     let args = Args::from_args();
 
     let mut settings = if let Some(path) = args.config_file {
+        record_input(&path);
         let file = fs::File::open(&path)?;
         serde_yaml::from_reader(&file).expect("Error reading settings:")
     } else {
@@ -114,11 +142,23 @@ fn main() -> Result<(), std::io::Error> {
         settings.media_path = media_path
     }
 
-    let root: Element = if let Some(path) = args.input_file {
-        let file = fs::File::open(&path)?;
-        serde_json::from_reader(&file).expect("error reading input!")
-    } else {
-        serde_json::from_reader(io::stdin()).expect("error reading input!")
+    // targets which do not process a document do not wait for input.
+    let root: Element = match args.cmd {
+        Commands::DumpConfig
+        | Commands::Links(_)
+        | Commands::LatexLog(_)
+        | Commands::Bibliography(_) => Element::Document(Document {
+            position: Span::any(),
+            content: vec![],
+        }),
+        _ => {
+            if let Some(path) = args.input_file {
+                let file = fs::File::open(&path)?;
+                serde_json::from_reader(&file).expect("error reading input!")
+            } else {
+                serde_json::from_reader(io::stdin()).expect("error reading input!")
+            }
+        }
     };
 
     match &args.cmd {
@@ -128,6 +168,26 @@ fn main() -> Result<(), std::io::Error> {
         ),
         Commands::Anchors(ref target_args) => find_target!(Targets::Anchors, settings, args)
             .export(&root, (), target_args, &mut io::stdout())?,
+        Commands::Links(ref target_args) => find_target!(Targets::Links, settings, args).export(
+            &root,
+            (),
+            target_args,
+            &mut io::stdout(),
+        )?,
+        Commands::Lint(ref target_args) => find_target!(Targets::Lint, settings, args).export(
+            &root,
+            &settings,
+            target_args,
+            &mut io::stdout(),
+        )?,
+        Commands::LinkAudit(ref target_args) => find_target!(Targets::LinkAudit, settings, args)
+            .export(&root, (), target_args, &mut io::stdout())?,
+        Commands::Accessibility(ref target_args) => find_target!(
+            Targets::Accessibility,
+            settings,
+            args
+        )
+        .export(&root, &settings, target_args, &mut io::stdout())?,
         Commands::Sections(ref target_args) => find_target!(Targets::Sections, settings, args)
             .export(&root, (), target_args, &mut io::stdout())?,
         Commands::SectionDeps(ref target_args) => find_target!(
@@ -147,6 +207,14 @@ fn main() -> Result<(), std::io::Error> {
             target_args,
             &mut io::stdout(),
         )?,
+        Commands::LatexLog(ref target_args) => find_target!(Targets::LatexLog, settings, args)
+            .export(&root, (), target_args, &mut io::stdout())?,
+        Commands::Bibliography(ref target_args) => find_target!(
+            Targets::Bibliography,
+            settings,
+            args
+        )
+        .export(&root, (), target_args, &mut io::stdout())?,
         Commands::PDF(ref target_args) => find_target!(Targets::PDF, settings, args).export(
             &root,
             (),
@@ -166,5 +234,9 @@ fn main() -> Result<(), std::io::Error> {
             &mut io::stdout(),
         )?,
     }
+
+    if let Some(ref path) = args.inputs_file {
+        write_inputs(&mut fs::File::create(path)?)?;
+    }
     Ok(())
 }
@@ -2,9 +2,14 @@
 //!
 //! This target is more a transformation than an export target. The output
 //! is the article with normalizing transformations applied.
+//!
+//! Templates which do not match the template specification are reported
+//! with the `template-spec` lint rule, before they reach any renderer.
 
 mod transformations;
 
+use crate::diagnostics::{has_errors, write_diagnostics};
+use crate::lint::LintTarget;
 use crate::preamble::*;
 use mediawiki_parser::transformations::TResult;
 use std::path::PathBuf;
@@ -17,13 +22,19 @@ pub struct NormalizeArgs {
     /// Path to the texvccheck binary (formula checking).
     #[structopt(parse(from_os_str), short = "p", long = "texvccheck-path")]
     texvccheck_path: Option<PathBuf>,
+    /// Title of the document, used in template diagnostics.
+    #[structopt(short = "d", long = "document-title", default_value = "input")]
+    document_title: String,
 }
 
 /// Applies some normalization transformations to an article
 /// and outputs its AST as JSON.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
-pub struct NormalizeTarget {}
+pub struct NormalizeTarget {
+    /// Exit with an error if a template does not match its specification.
+    fail_on_invalid_templates: bool,
+}
 
 /// Applies all transformations which should happen before section transclusion.
 pub fn normalize(mut root: Element, settings: &Settings, checker: &dyn TexChecker) -> TResult {
@@ -56,7 +67,21 @@ impl<'a, 's> Target<&'a NormalizeArgs, &'s Settings> for NormalizeTarget {
         };
 
         match normalize(root, &settings, &checker) {
-            Ok(root) => serde_json::to_writer(out, &root).expect("could not serialize result!"),
+            Ok(root) => {
+                let diagnostics = LintTarget::only(&["template-spec"]).lint(
+                    &root,
+                    &settings,
+                    &args.document_title,
+                )?;
+                write_diagnostics(&diagnostics, &mut io::stderr())?;
+                if self.fail_on_invalid_templates && has_errors(&diagnostics) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "templates do not match the template specification!",
+                    ));
+                }
+                serde_json::to_writer(out, &root).expect("could not serialize result!")
+            }
             Err(err) => {
                 eprintln!("{}", &err);
                 serde_json::to_writer(out, &err).expect("could not serialize error!");
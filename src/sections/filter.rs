@@ -1,51 +1,58 @@
 //! Helpers for the sections target related to extracting section content.
 
-use super::finder::SectionFinder;
+use super::finder::{section_boundary, section_tag, BoundaryKind, SectionBoundary, SectionFinder};
 use crate::preamble::*;
 use mediawiki_parser::transformations::*;
+use std::cell::Cell;
+use std::ptr;
 
 /// Paramters for section filtering transformation.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct SectionFilter<'b, 'e: 'b> {
-    pub begin: &'b Vec<&'e Element>,
-    pub end: &'b Vec<&'e Element>,
-    /// wether to include nodes before end of the section,
-    /// even if no start is present in the current subtree.
-    pub include_pre: bool,
+    /// label of the extracted section.
+    pub label: &'b str,
+    /// paths to all tags of the extracted section.
+    pub boundaries: &'b [SectionBoundary<'e>],
+    /// wether to keep section tags of other labels in the output.
+    pub keep_other_tags: bool,
+    /// wether the current position is between a begin and end tag.
+    pub inside: Cell<bool>,
 }
 
 impl<'a, 'b: 'a> SectionFilter<'a, 'b> {
     /// Extract a list of nodes forming a section from an input ast.
-    pub fn extract(label: &str, root: &Element) -> Option<Vec<Element>> {
-        let start = SectionFinder::get_start(root, label);
-        let end = SectionFinder::get_end(root, label);
-        let (start, end) = match (start, end) {
-            (Some(start), Some(end)) => (start, end),
-            _ => return None,
-        };
+    ///
+    /// If the label occurs multiple times, the content of all
+    /// begin / end pairs is concatenated.
+    pub fn extract(label: &str, root: &Element, keep_other_tags: bool) -> Option<Vec<Element>> {
+        let boundaries = SectionFinder::find_boundaries(root, label);
+        let has_kind = |kind| boundaries.iter().any(|b| b.kind == kind);
+        if !has_kind(BoundaryKind::Begin) || !has_kind(BoundaryKind::End) {
+            return None;
+        }
 
-        // lowest common node
-        let mut common = None;
-        for ps in start.iter().rev() {
-            for pe in end.iter().rev() {
-                if pe == ps {
-                    common = Some(ps);
-                    break;
-                }
-            }
-            if common.is_some() {
-                break;
-            }
+        // lowest common node of all section tags
+        let first = &boundaries[0].path;
+        let mut depth = 0;
+        while depth < first.len()
+            && boundaries.iter().all(|b| {
+                b.path
+                    .get(depth)
+                    .map_or(false, |e| ptr::eq(*e, first[depth]))
+            })
+        {
+            depth += 1;
         }
-        let common = match common {
-            Some(c) => c,
-            None => return None,
+        let common = match depth {
+            0 => return None,
+            d => first[d - 1],
         };
 
         let filter = SectionFilter {
-            begin: &start,
-            end: &end,
-            include_pre: false,
+            label,
+            boundaries: &boundaries,
+            keep_other_tags,
+            inside: Cell::new(false),
         };
 
         let result =
@@ -53,10 +60,17 @@ impl<'a, 'b: 'a> SectionFilter<'a, 'b> {
 
         extract_content(result)
     }
+
+    /// Is `root` a section tag of this section or an ancestor of one?
+    fn contains_boundary(&self, root: &Element) -> bool {
+        self.boundaries
+            .iter()
+            .any(|b| b.path.iter().any(|e| ptr::eq(*e, root)))
+    }
 }
 
 /// Recursively trim a subtree to only contain the elements
-/// enclosed by the section paths in `SectionFilter`.
+/// enclosed by the section tags in `SectionFilter`.
 fn filter_section_element(root: &Element, path: &[&Element], settings: &SectionFilter) -> TResult {
     recurse_clone_template(
         &filter_section_element,
@@ -68,7 +82,10 @@ fn filter_section_element(root: &Element, path: &[&Element], settings: &SectionF
 }
 
 /// Recursively trim a list of elments to only contain the elements
-/// enclosed by the section paths in `SectionFilter`.
+/// enclosed by the section tags in `SectionFilter`.
+///
+/// Elements containing a section tag are kept with their content trimmed,
+/// so begin and end may be located at different depths.
 fn filter_section_subtree<'a>(
     _func: &TFunc<&SectionFilter>,
     content: &[Element],
@@ -76,37 +93,27 @@ fn filter_section_subtree<'a>(
     settings: &SectionFilter,
 ) -> TListResult {
     let mut result = vec![];
-    let mut found_begin = false;
 
     for child in content {
-        if settings.begin.contains(&child) {
-            found_begin = true;
+        // the tags of the section itself are never exported
+        if let Some(kind) = section_boundary(child, settings.label) {
+            settings.inside.set(kind == BoundaryKind::Begin);
+            continue;
+        }
 
-            // ignore the starting section tag
-            if Some(&child) != settings.begin.last() {
-                result.push(
-                    filter_section_element(child, path, &settings.clone())
-                        .expect("error in section filter"),
-                );
-            }
+        if settings.contains_boundary(child) {
+            result.push(filter_section_element(child, path, settings)?);
             continue;
         }
-        if settings.end.contains(&child) {
-            let mut child_settings = settings.clone();
-            child_settings.include_pre = true;
 
-            // ignore the ending section tag
-            if Some(&child) != settings.end.last() {
-                result.push(
-                    filter_section_element(child, path, &child_settings)
-                        .expect("error in section filter"),
-                );
-            }
-            break;
+        if !settings.inside.get() {
+            continue;
         }
 
-        if found_begin || settings.include_pre {
+        if settings.keep_other_tags {
             result.push(child.clone());
+        } else if section_tag(child).is_none() {
+            result.push(filter_section_element(child, path, settings)?);
         }
     }
     Ok(result)
@@ -3,62 +3,90 @@
 
 use crate::preamble::*;
 
-/// Return a path to the start / end of a section
+/// Kind of a section tag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoundaryKind {
+    Begin,
+    End,
+}
+
+/// A `<section />` tag of the searched label with its path from the root.
+#[derive(Debug, Clone)]
+pub struct SectionBoundary<'e> {
+    pub kind: BoundaryKind,
+    /// path to the tag, the tag itself is the last element.
+    pub path: Vec<&'e Element>,
+}
+
+/// Returns the paths to all starts / ends of a section.
 #[derive(Default)]
 pub struct SectionFinder<'e, 'a> {
     /// label of the section to find.
     pub label: &'a str,
-    /// get start or end of section?
-    pub begin: bool,
     path: Vec<&'e Element>,
-    /// the resulting path.
-    pub result: Option<Vec<&'e Element>>,
+    /// the resulting boundaries in document order.
+    pub result: Vec<SectionBoundary<'e>>,
+}
+
+/// normalize a section label for comparison.
+fn normalize_label(label: &str) -> String {
+    label.trim().to_lowercase().replace(' ', "_")
+}
+
+/// Returns the kind and label of a `<section />` tag.
+pub fn section_tag(root: &Element) -> Option<(BoundaryKind, &str)> {
+    if let Element::HtmlTag(ref tag) = *root {
+        if tag.name.to_lowercase() != "section" {
+            return None;
+        }
+        for attr in &tag.attributes {
+            match attr.key.to_lowercase().trim() {
+                "begin" => return Some((BoundaryKind::Begin, &attr.value)),
+                "end" => return Some((BoundaryKind::End, &attr.value)),
+                _ => (),
+            }
+        }
+    }
+    None
+}
+
+/// Returns the boundary kind if `root` is a section tag of `label`.
+pub fn section_boundary(root: &Element, label: &str) -> Option<BoundaryKind> {
+    match section_tag(root) {
+        Some((kind, tag_label)) if normalize_label(tag_label) == normalize_label(label) => {
+            Some(kind)
+        }
+        _ => None,
+    }
 }
 
 impl<'e, 'a> Traversion<'e, ()> for SectionFinder<'e, 'a> {
     path_methods!('e);
 
     fn work(&mut self, root: &'e Element, _: (), _: &mut io::Write) -> io::Result<bool> {
-        // end recursion if result is found
-        if self.result.is_some() {
-            return Ok(false);
+        if let Some(kind) = section_boundary(root, self.label) {
+            self.result.push(SectionBoundary {
+                kind,
+                path: self.path.clone(),
+            });
         }
-
-        if let Element::HtmlTag(ref tag) = *root {
-            if tag.name.to_lowercase() == "section" {
-                for attr in &tag.attributes {
-                    let norm = |s: &str| s.trim().to_lowercase().replace(' ', "_");
-                    if attr.key.to_lowercase() == if self.begin { "begin" } else { "end" }
-                        && norm(&attr.value) == norm(self.label)
-                    {
-                        self.result = Some(self.path.clone());
-                    }
-                }
-            }
-        };
         Ok(true)
     }
 }
 
 impl<'a, 'e> SectionFinder<'e, 'a> {
-    fn find_path(root: &'e Element, label: &'a str, begin: bool) -> Option<Vec<&'e Element>> {
+    /// Find all begin and end tags of a section label in document order.
+    pub fn find_boundaries(root: &'e Element, label: &'a str) -> Vec<SectionBoundary<'e>> {
         let mut finder = SectionFinder {
             label,
-            begin,
             path: vec![],
-            result: None,
+            result: vec![],
         };
 
         if finder.run(root, (), &mut vec![]).is_ok() {
             finder.result
         } else {
-            None
+            vec![]
         }
     }
-    pub fn get_start(root: &'e Element, label: &'a str) -> Option<Vec<&'e Element>> {
-        SectionFinder::find_path(root, label, true)
-    }
-    pub fn get_end(root: &'e Element, label: &'a str) -> Option<Vec<&'e Element>> {
-        SectionFinder::find_path(root, label, false)
-    }
 }
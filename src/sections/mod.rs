@@ -3,6 +3,9 @@
 //! This target operates on the same syntax tree as the `deps` target. It extracts
 //! parts of the document tree marked by `<section />` tags and writes them to a
 //! directory specified through the transformation settings in the YAML format.
+//!
+//! Sections may be nested, overlap with sections of other labels or consist of
+//! multiple begin / end pairs, which are concatenated like in MediaWiki.
 
 use crate::preamble::*;
 use serde_json;
@@ -20,9 +23,21 @@ pub struct SectionsArgs {
 }
 
 /// Write document section to the filesystem.
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(default)]
-pub struct SectionsTarget {}
+pub struct SectionsTarget {
+    /// Keep `<section />` tags of other labels which are nested in
+    /// or overlap with the extracted section.
+    keep_other_tags: bool,
+}
+
+impl Default for SectionsTarget {
+    fn default() -> SectionsTarget {
+        SectionsTarget {
+            keep_other_tags: true,
+        }
+    }
+}
 
 impl<'a> Target<&'a SectionsArgs, ()> for SectionsTarget {
     fn target_type(&self) -> TargetType {
@@ -36,7 +51,8 @@ impl<'a> Target<&'a SectionsArgs, ()> for SectionsTarget {
         args: &'a SectionsArgs,
         out: &mut io::Write,
     ) -> io::Result<()> {
-        let inter = match filter::SectionFilter::extract(&args.section, root) {
+        let inter = match filter::SectionFilter::extract(&args.section, root, self.keep_other_tags)
+        {
             Some(inter) => inter,
             None => panic!(
                 "could not find section \"{}\" in this document!",
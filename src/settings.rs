@@ -3,8 +3,9 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::{
-    AnchorsTarget, ComposeTarget, HTMLTarget, LatexTarget, MediaDepTarget, NormalizeTarget,
-    PDFTarget, SectionDepTarget, SectionsTarget, StatsTarget, Targets,
+    AccessibilityTarget, AnchorsTarget, BibliographyTarget, ComposeTarget, HTMLTarget,
+    LatexLogTarget, LatexTarget, LinkAuditTarget, LinksTarget, LintTarget, MediaDepTarget,
+    NormalizeTarget, PDFTarget, SectionDepTarget, SectionsTarget, StatsTarget, Targets,
 };
 
 macro_rules! string_vec {
@@ -54,7 +55,13 @@ impl Default for Settings {
                         Targets::Normalize(NormalizeTarget::default()),
                         Targets::Compose(ComposeTarget::default()),
                         Targets::Anchors(AnchorsTarget::default()),
+                        Targets::Links(LinksTarget::default()),
+                        Targets::Lint(LintTarget::default()),
+                        Targets::LinkAudit(LinkAuditTarget::default()),
+                        Targets::Accessibility(AccessibilityTarget::default()),
                         Targets::Latex(LatexTarget::default()),
+                        Targets::LatexLog(LatexLogTarget::default()),
+                        Targets::Bibliography(BibliographyTarget::default()),
                         Targets::PDF(PDFTarget::default()),
                         Targets::Stats(StatsTarget::default()),
                         Targets::HTML(HTMLTarget::default()),
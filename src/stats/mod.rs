@@ -1,31 +1,84 @@
 //! Implements the `stats` target which extracts various statistical
 //! information from the document tree.
+//!
+//! Optionally, internal references which do not resolve are reported
+//! with their position, a classification and suggestions, and content
+//! statistics are broken down along the heading tree.
+use crate::anchors::AnchorIndex;
+use crate::diagnostics::write_diagnostics;
 use crate::preamble::*;
+use crate::title;
 use serde_json;
 use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::io;
+use std::path::PathBuf;
+use std::slice;
 use structopt::StructOpt;
 
+mod references;
+mod sections;
+
+pub use self::references::{suggest_anchors, UnresolvedKind, UnresolvedReference};
+pub use self::sections::{SectionFormat, SectionStats};
+
 #[derive(Debug, StructOpt)]
 pub struct StatsArgs {
     /// Title of the document beeing processed.
     document_title: String,
 
     /// Path to a list of link targets (anchors) available in the export.
-    #[structopt(parse(try_from_str = "load_anchor_set"))]
-    available_anchors: HashSet<String>,
+    #[structopt(parse(try_from_str = "load_anchor_index"))]
+    available_anchors: AnchorIndex,
+
+    /// Anchors of the articles before markers were applied,
+    /// to detect references to excluded content.
+    #[structopt(
+        parse(try_from_str = "load_anchor_index"),
+        short = "s",
+        long = "source-anchors"
+    )]
+    source_anchors: Option<AnchorIndex>,
+
+    /// Write a JSON report of unresolved references to this file.
+    #[structopt(parse(from_os_str), short = "r", long = "report")]
+    report_path: Option<PathBuf>,
+
+    /// Write content statistics per section to this file.
+    #[structopt(parse(from_os_str), short = "t", long = "section-stats")]
+    section_stats_path: Option<PathBuf>,
 }
 
 /// Dump stats to stdout as json.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(default)]
-pub struct StatsTarget {}
+pub struct StatsTarget {
+    /// Print unresolved references to stderr.
+    check_references: bool,
+    /// Maximum number of anchors suggested for an unresolved reference.
+    max_suggestions: usize,
+    /// Output format of the per-section statistics.
+    section_format: SectionFormat,
+}
+
+impl Default for StatsTarget {
+    fn default() -> StatsTarget {
+        StatsTarget {
+            check_references: false,
+            max_suggestions: 3,
+            section_format: SectionFormat::default(),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 struct Stats<'e> {
     #[serde(skip)]
     pub path: Vec<&'e Element>,
 
+    /// Title of the document these stats describe
+    pub document_title: String,
+
     /// The original document length
     pub line_count: usize,
 
@@ -40,6 +93,10 @@ struct Stats<'e> {
 
     /// List of reference targets with no corresponding anchors in the export
     pub unresolved_references: HashSet<String>,
+
+    /// Details of all unresolved references
+    #[serde(skip)]
+    pub unresolved: Vec<UnresolvedReference>,
 }
 
 impl<'e, 's: 'e, 'a> Traversion<'e, (&'s Settings, &'a StatsArgs)> for Stats<'e> {
@@ -61,18 +118,27 @@ impl<'e, 's: 'e, 'a> Traversion<'e, (&'s Settings, &'a StatsArgs)> for Stats<'e>
                     let target = target.trim().trim_left_matches(':').to_string();
 
                     self.reference_targets.insert(target.clone());
-                    let anchor = matching_anchor(&target, &args.available_anchors);
+                    let doc_title = &args.document_title;
+                    let anchor = matching_anchor(&target, doc_title, &args.available_anchors);
                     if anchor.is_none() {
-                        let enc_target = mw_enc(&target);
-                        // if a prefix exists, the target should exist as well,
-                        // otherwise this reference is unresolved
-                        let article_exists = args
-                            .available_anchors
-                            .iter()
-                            .any(|anchor| enc_target.starts_with(anchor));
-                        if article_exists {
-                            self.unresolved_references.insert(target);
+                        let anchor = title::target_anchor(&target, doc_title);
+                        let kind = UnresolvedKind::classify(
+                            &anchor,
+                            &args.available_anchors,
+                            args.source_anchors.as_ref(),
+                        );
+                        // references to articles outside of the export are wiki links.
+                        if kind != UnresolvedKind::MissingArticle {
+                            self.unresolved_references.insert(target.clone());
                         }
+                        self.unresolved.push(UnresolvedReference {
+                            target,
+                            anchor,
+                            caption: extract_plain_text(&iref.caption).trim().to_string(),
+                            position: iref.position.clone(),
+                            kind,
+                            suggestions: vec![],
+                        });
                     }
                 }
             }
@@ -100,9 +166,48 @@ impl<'a, 's> Target<&'a StatsArgs, &'s Settings> for StatsTarget {
     ) -> io::Result<()> {
         let mut stats = Stats::default();
 
+        stats.document_title = args.document_title.clone();
         stats.line_count = root.get_position().end.line;
         stats.run(root, (settings, args), out)?;
 
+        for reference in &mut stats.unresolved {
+            reference.suggestions = suggest_anchors(
+                &reference.anchor,
+                &args.available_anchors,
+                self.max_suggestions,
+            );
+        }
+
+        if self.check_references {
+            let diagnostics: Vec<_> = stats
+                .unresolved
+                .iter()
+                .map(|r| r.to_diagnostic(&args.document_title))
+                .collect();
+            write_diagnostics(&diagnostics, &mut io::stderr())?;
+        }
+        if let Some(ref path) = args.report_path {
+            let file = fs::File::create(path)?;
+            serde_json::to_writer(&file, &stats.unresolved)
+                .expect("could not serialize unresolved references!");
+        }
+        if let Some(ref path) = args.section_stats_path {
+            let content = match root {
+                Element::Document(ref document) => &document.content[..],
+                _ => slice::from_ref(root),
+            };
+            let section_stats = SectionStats::new(&args.document_title, 0, content, settings);
+            let mut file = fs::File::create(path)?;
+            match self.section_format {
+                SectionFormat::Json => serde_json::to_writer(&file, &section_stats)
+                    .expect("could not serialize section stats!"),
+                SectionFormat::Csv => {
+                    writeln!(&mut file, "{}", sections::CSV_HEADER)?;
+                    section_stats.write_csv(&[], &mut file)?;
+                }
+            }
+        }
+
         writeln!(
             out,
             "{}",
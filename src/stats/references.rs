@@ -0,0 +1,102 @@
+//! Classification of internal references which do not resolve to an anchor
+//! of the export.
+
+use crate::anchors::AnchorIndex;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::preamble::*;
+use crate::title;
+
+/// Why an internal reference could not be resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnresolvedKind {
+    /// The referenced article is not part of the export.
+    MissingArticle,
+    /// The article is exported, but has no such heading or anchor.
+    MissingHeading,
+    /// The target exists in the source articles, but was excluded by markers.
+    ExcludedByMarkers,
+}
+
+/// An internal reference without a matching anchor in the export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnresolvedReference {
+    /// The reference target as written in the source.
+    pub target: String,
+    /// The anchor the target was expected at.
+    pub anchor: String,
+    /// Plain text caption of the reference.
+    pub caption: String,
+    pub position: Span,
+    pub kind: UnresolvedKind,
+    /// Existing anchors closest to the expected anchor.
+    pub suggestions: Vec<String>,
+}
+
+impl UnresolvedKind {
+    /// Classify a target which is not in `anchors`. `source_anchors` are
+    /// the anchors of the articles before markers were applied, if known.
+    pub fn classify(
+        anchor: &str,
+        anchors: &AnchorIndex,
+        source_anchors: Option<&AnchorIndex>,
+    ) -> UnresolvedKind {
+        let article = title::split_target(anchor).0;
+        if source_anchors.map(|s| s.contains(anchor)).unwrap_or(false) {
+            UnresolvedKind::ExcludedByMarkers
+        } else if anchors.contains(article) {
+            UnresolvedKind::MissingHeading
+        } else {
+            UnresolvedKind::MissingArticle
+        }
+    }
+}
+
+impl UnresolvedReference {
+    pub fn to_diagnostic(&self, document: &str) -> Diagnostic {
+        let (severity, kind, reason) = match self.kind {
+            UnresolvedKind::MissingArticle => (
+                Severity::Info,
+                "missing-article",
+                "the article is not part of the export",
+            ),
+            UnresolvedKind::MissingHeading => (
+                Severity::Warning,
+                "missing-heading",
+                "the article has no such heading or anchor",
+            ),
+            UnresolvedKind::ExcludedByMarkers => (
+                Severity::Warning,
+                "excluded-reference",
+                "the target is excluded by markers",
+            ),
+        };
+        let mut message = format!("unresolved reference to {:?}: {}.", &self.target, reason);
+        if !self.suggestions.is_empty() {
+            message.push_str(&format!(" Did you mean {}?", self.suggestions.join(", ")));
+        }
+        Diagnostic::new(severity, kind, message, document, &self.position)
+    }
+}
+
+/// Find up to `count` anchors closest to `anchor` by edit distance.
+/// Anchors of the same article are preferred, if it is exported.
+/// Anchors are compared and returned in their readable (decoded) form.
+pub fn suggest_anchors(anchor: &str, anchors: &AnchorIndex, count: usize) -> Vec<String> {
+    let article = title::split_target(anchor).0;
+    let prefix = format!("{}#", article);
+    let same_article = anchors.anchors().any(|a| a.starts_with(&prefix));
+
+    let anchor = title::display_anchor(anchor);
+    let max_distance = anchor.chars().count() / 2;
+    let mut candidates: Vec<(usize, String)> = anchors
+        .anchors()
+        .filter(|a| !same_article || a.starts_with(&prefix))
+        .map(|a| title::display_anchor(a))
+        .map(|a| (edit_distance(&anchor, &a), a))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates.into_iter().take(count).map(|(_, a)| a).collect()
+}
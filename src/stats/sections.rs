@@ -0,0 +1,226 @@
+//! Content statistics along the heading tree of a document.
+
+use crate::preamble::*;
+use mfnf_template_spec::{parse_template, KnownTemplate};
+
+/// Output format of the per-section statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionFormat {
+    /// The section tree as JSON.
+    Json,
+    /// One CSV row per section, in document order.
+    Csv,
+}
+
+impl Default for SectionFormat {
+    fn default() -> SectionFormat {
+        SectionFormat::Json
+    }
+}
+
+/// Statistics of a section, including its subsections.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SectionStats {
+    /// Plain text caption of the section heading.
+    pub title: String,
+    pub depth: usize,
+    /// Words of the text, excluding markup and formulas.
+    pub words: usize,
+    pub sentences: usize,
+    pub syllables: usize,
+    pub inline_formulas: usize,
+    pub display_formulas: usize,
+    pub exercises: usize,
+    pub definitions: usize,
+    pub theorems: usize,
+    /// Amstad's German adaption of the Flesch reading ease.
+    pub readability: Option<f64>,
+    pub sections: Vec<SectionStats>,
+}
+
+impl SectionStats {
+    /// Collect the statistics of a section with the given content.
+    pub fn new(
+        title: &str,
+        depth: usize,
+        content: &[Element],
+        settings: &Settings,
+    ) -> SectionStats {
+        let mut counter = SectionCounter {
+            path: vec![],
+            stats: SectionStats {
+                title: title.to_string(),
+                depth,
+                ..SectionStats::default()
+            },
+        };
+        counter
+            .run_vec(content, settings, &mut vec![])
+            .expect("unexpected IO error while counting!");
+
+        let mut stats = counter.stats;
+        if stats.words > 0 {
+            let sentence_length = stats.words as f64 / stats.sentences.max(1) as f64;
+            let word_length = stats.syllables as f64 / stats.words as f64;
+            stats.readability = Some(180.0 - sentence_length - 58.5 * word_length);
+        }
+        stats
+    }
+
+    fn add(&mut self, other: &SectionStats) {
+        self.words += other.words;
+        self.sentences += other.sentences;
+        self.syllables += other.syllables;
+        self.inline_formulas += other.inline_formulas;
+        self.display_formulas += other.display_formulas;
+        self.exercises += other.exercises;
+        self.definitions += other.definitions;
+        self.theorems += other.theorems;
+    }
+
+    fn count_text(&mut self, text: &str) {
+        for token in text.split_whitespace() {
+            // a sentence may also end with a number or a lone `.` after a formula
+            if token.ends_with(|c: char| c == '.' || c == '!' || c == '?')
+                && !is_abbreviation(token)
+            {
+                self.sentences += 1;
+            }
+            if !token.chars().any(|c| c.is_alphabetic()) {
+                continue;
+            }
+            self.words += 1;
+            self.syllables += count_syllables(token);
+        }
+    }
+
+    /// Write this section and its subsections as CSV rows.
+    pub fn write_csv(&self, parents: &[&str], out: &mut io::Write) -> io::Result<()> {
+        let mut path = parents.to_vec();
+        path.push(&self.title);
+
+        writeln!(
+            out,
+            "\"{}\",{},{},{},{},{},{},{},{},{},{}",
+            path.join(" / ").replace('"', "\"\""),
+            self.depth,
+            self.words,
+            self.sentences,
+            self.syllables,
+            self.inline_formulas,
+            self.display_formulas,
+            self.exercises,
+            self.definitions,
+            self.theorems,
+            self.readability
+                .map(|r| format!("{:.1}", r))
+                .unwrap_or_default()
+        )?;
+        for section in &self.sections {
+            section.write_csv(&path, out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Header line of the CSV output.
+pub const CSV_HEADER: &str = "section,depth,words,sentences,syllables,inline_formulas,\
+                              display_formulas,exercises,definitions,theorems,readability";
+
+/// Common German abbreviations which do not end a sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "bzw", "vgl", "bspw", "ggf", "sog", "evtl", "ca", "inkl", "bzgl", "nr", "abb", "kap", "def",
+];
+
+/// Is this token an abbreviation like `z.`, `d.h.` or `bzw.`?
+fn is_abbreviation(token: &str) -> bool {
+    let word = token.trim_start_matches(|c: char| !c.is_alphanumeric());
+    if !word.ends_with('.') {
+        return false;
+    }
+    let parts: Vec<_> = word.split('.').filter(|p| !p.is_empty()).collect();
+    let single_letters = !parts.is_empty()
+        && parts
+            .iter()
+            .all(|p| p.chars().count() == 1 && p.chars().all(|c| c.is_alphabetic()));
+    single_letters || ABBREVIATIONS.contains(&word.trim_end_matches('.').to_lowercase().as_str())
+}
+
+/// Approximate the syllables of a German word by its vowel groups.
+fn count_syllables(word: &str) -> usize {
+    let is_vowel = |c: char| "aeiouyäöü".contains(c);
+    let mut count = 0;
+    let mut last_vowel = false;
+    for c in word.chars().flat_map(|c| c.to_lowercase()) {
+        let vowel = is_vowel(c);
+        if vowel && !last_vowel {
+            count += 1;
+        }
+        last_vowel = vowel;
+    }
+    count.max(1)
+}
+
+struct SectionCounter<'e> {
+    path: Vec<&'e Element>,
+    stats: SectionStats,
+}
+
+impl<'e, 's: 'e> Traversion<'e, &'s Settings> for SectionCounter<'e> {
+    path_methods!('e);
+
+    fn work(
+        &mut self,
+        root: &'e Element,
+        settings: &'s Settings,
+        out: &mut io::Write,
+    ) -> io::Result<bool> {
+        Ok(match root {
+            Element::Heading(ref heading) => {
+                let caption = extract_plain_text(&heading.caption);
+                let child =
+                    SectionStats::new(caption.trim(), heading.depth, &heading.content, settings);
+                self.stats.add(&child);
+                self.stats.sections.push(child);
+                false
+            }
+            Element::Text(ref text) => {
+                self.stats.count_text(&text.text);
+                false
+            }
+            Element::Formatted(Formatted {
+                markup: MarkupType::Math,
+                ..
+            }) => {
+                self.stats.inline_formulas += 1;
+                false
+            }
+            Element::Template(ref template) => {
+                match parse_template(template) {
+                    Some(KnownTemplate::Formula(_)) => {
+                        self.stats.display_formulas += 1;
+                        return Ok(false);
+                    }
+                    Some(KnownTemplate::Exercise(_)) | Some(KnownTemplate::GroupExercise(_)) => {
+                        self.stats.exercises += 1
+                    }
+                    Some(KnownTemplate::Definition(_)) => self.stats.definitions += 1,
+                    Some(KnownTemplate::Theorem(_)) => self.stats.theorems += 1,
+                    _ => (),
+                }
+                // only count the arguments, not the template name.
+                self.run_vec(&template.content, settings, out)?;
+                false
+            }
+            Element::InternalReference(ref iref) => {
+                if !is_file(iref, settings) {
+                    self.run_vec(&iref.caption, settings, out)?;
+                }
+                false
+            }
+            Element::Comment(_) => false,
+            _ => true,
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use super::util::{document, heading, paragraph, spec_template, text};
+use crate::anchors::{extract_anchor_entry, AnchorIndex, AnchorKind};
+use crate::preamble::*;
+use mfnf_template_spec::KnownTemplate;
+
+#[test]
+fn parse_json_and_plain_lines() {
+    let index = AnchorIndex::parse(concat!(
+        "Folgen\n",
+        "\n",
+        "{\"anchor\":\"Folgen#Anker:Satz\",\"kind\":\"template\",\"template\":\"theorem\",",
+        "\"caption\":\"Satz\",\"heading_path\":[\"Konvergenz\"],",
+        "\"position\":{\"start\":{\"offset\":0,\"line\":3,\"col\":1},",
+        "\"end\":{\"offset\":0,\"line\":3,\"col\":1}}}\n",
+        "  Folgen#Konvergenz  \n",
+    ))
+    .expect("could not parse anchors!");
+
+    assert!(index.contains("Folgen"));
+    assert!(index.contains("Folgen#Konvergenz"));
+    assert!(index.entry("Folgen").is_none());
+    assert_eq!(index.anchors().count(), 3);
+
+    let entry = index.entry("Folgen#Anker:Satz").expect("missing entry!");
+    assert_eq!(entry.kind, AnchorKind::Template);
+    assert_eq!(entry.template, Some("theorem".to_string()));
+    assert_eq!(entry.caption, "Satz");
+    assert_eq!(entry.heading_path, vec!["Konvergenz"]);
+    assert_eq!(entry.position.start.line, 3);
+}
+
+#[test]
+fn parse_legacy_plain_list() {
+    let index =
+        AnchorIndex::parse("Folgen\nFolgen#.C3.9Cbersicht\n\n").expect("could not parse anchors!");
+    let mut anchors: Vec<_> = index.anchors().cloned().collect();
+    anchors.sort();
+    assert_eq!(anchors, vec!["Folgen", "Folgen#.C3.9Cbersicht"]);
+    assert!(anchors.iter().all(|a| index.entry(a).is_none()));
+}
+
+#[test]
+fn parse_invalid_json() {
+    let err = AnchorIndex::parse("Folgen\n{\"anchor\": 1}\n").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn document_and_heading_entries() {
+    let root = document(vec![]);
+    let entry = extract_anchor_entry(&root, "Folgen", vec![]).expect("no document anchor!");
+    assert_eq!(entry.kind, AnchorKind::Document);
+    assert_eq!(entry.caption, "Folgen");
+    assert_eq!(entry.template, None);
+
+    let root = heading(3, " Beispiel ", vec![]);
+    let path = vec!["Konvergenz".to_string()];
+    let entry = extract_anchor_entry(&root, "Folgen", path.clone()).expect("no heading anchor!");
+    assert_eq!(entry.kind, AnchorKind::Heading);
+    assert_eq!(entry.caption, "Beispiel");
+    assert_eq!(entry.heading_path, path);
+
+    assert!(extract_anchor_entry(&paragraph(vec![text("x")]), "Folgen", vec![]).is_none());
+}
+
+#[test]
+fn template_entries() {
+    let title = vec![text("Satz vom Maximum")];
+    let root = spec_template(|t| t.find("title").is_some(), &[("title", title)]);
+    let entry = extract_anchor_entry(&root, "Folgen", vec![]).expect("no template anchor!");
+    assert_eq!(entry.kind, AnchorKind::Template);
+    assert!(entry.template.is_some());
+    assert_eq!(entry.caption, "Satz vom Maximum");
+
+    let root = spec_template(
+        |t| match t {
+            KnownTemplate::Anchor(_) => true,
+            _ => false,
+        },
+        &[],
+    );
+    let entry = extract_anchor_entry(&root, "Folgen", vec![]).expect("no anchor template!");
+    assert_eq!(entry.kind, AnchorKind::Anchor);
+    assert_eq!(entry.template, None);
+    assert_eq!(entry.caption, "x");
+}
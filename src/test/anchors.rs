@@ -0,0 +1,90 @@
+use super::util::{document, heading, span, spec_template};
+use crate::anchors::{collect_anchors, AnchorEntry, AnchorKind, AnchorRegistry};
+use crate::labels::anchor_slug;
+use crate::latex::{LatexArgs, LatexTarget};
+use crate::preamble::*;
+use mfnf_template_spec::KnownTemplate;
+use structopt::StructOpt;
+
+fn entry(anchor: &str, line: usize) -> AnchorEntry {
+    AnchorEntry {
+        anchor: anchor.to_string(),
+        kind: AnchorKind::Heading,
+        template: None,
+        caption: String::new(),
+        heading_path: vec![],
+        position: span(line),
+    }
+}
+
+#[test]
+fn duplicate_anchors_are_suffixed() {
+    let mut entries = vec![
+        entry("Doc#A", 1),
+        entry("Doc#A", 2),
+        entry("Doc#A_2", 3),
+        entry("Doc#A", 4),
+    ];
+    let registry = AnchorRegistry::new(&mut entries);
+    let anchors: Vec<_> = entries.iter().map(|e| e.anchor.as_str()).collect();
+    assert_eq!(anchors, vec!["Doc#A", "Doc#A_2", "Doc#A_2_2", "Doc#A_3"]);
+    assert_eq!(registry.collisions.len(), 3);
+    assert_eq!(registry.collisions[1].anchor, "Doc#A_2");
+    assert_eq!(registry.collisions[1].first, entries[1].position);
+}
+
+#[test]
+fn anchors_are_resolved_by_position() {
+    let mut entries = vec![entry("Doc#A", 1), entry("Doc#B", 2), entry("Doc#A", 3)];
+    let registry = AnchorRegistry::new(&mut entries);
+    assert_eq!(registry.resolve("Doc#A", &span(3)), "Doc#A_2");
+    assert_eq!(registry.resolve("Doc#B", &span(2)), "Doc#B");
+    assert_eq!(registry.resolve("Doc#A", &span(1)), "Doc#A");
+    assert_eq!(registry.resolve("Doc#C", &span(4)), "Doc#C");
+}
+
+#[test]
+fn skipped_anchors_keep_their_suffix() {
+    let with_position = |element: Element, line: usize| match element {
+        Element::Heading(mut heading) => {
+            heading.position = span(line);
+            Element::Heading(heading)
+        }
+        Element::Template(mut template) => {
+            template.position = span(line);
+            Element::Template(template)
+        }
+        _ => unreachable!(),
+    };
+    // LaTeX does not render the content of `noprint` by default.
+    let noprint = spec_template(
+        |t| match t {
+            KnownTemplate::NoPrint(_) => true,
+            _ => false,
+        },
+        &[("content", vec![with_position(heading(2, "A", vec![]), 2)])],
+    );
+    let root = document(vec![
+        with_position(heading(2, "A", vec![]), 1),
+        with_position(noprint, 4),
+        with_position(heading(2, "A", vec![]), 3),
+    ]);
+
+    let (entries, _) = collect_anchors(&root, "test_doc");
+    let labels: Vec<_> = entries
+        .iter()
+        .skip(1)
+        .map(|e| format!("{{{}}}", anchor_slug(&e.anchor)))
+        .collect();
+    assert_eq!(labels.len(), 3);
+
+    let args = LatexArgs::from_iter(["test", "test_doc", "src/test/test.anchors"].iter());
+    let mut res = vec![];
+    LatexTarget::default()
+        .export(&root, &Settings::default(), &args, &mut res)
+        .expect("export failed!");
+    let output = String::from_utf8_lossy(&res);
+    assert!(output.contains(&labels[0]), "{}", &output);
+    assert!(!output.contains(&labels[1]), "{}", &output);
+    assert!(output.contains(&labels[2]), "{}", &output);
+}
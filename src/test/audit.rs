@@ -0,0 +1,192 @@
+use super::util::{html_tag, internal_reference, paragraph, text};
+use crate::audit::accessibility::AccessibilityChecker;
+use crate::audit::split_url;
+use crate::preamble::*;
+
+fn parts(scheme: &str, host: &str) -> Result<(String, String), String> {
+    Ok((scheme.to_string(), host.to_string()))
+}
+
+/// Kinds of the accessibility problems of a paragraph with the given content.
+fn accessibility(content: Vec<Element>) -> Vec<String> {
+    let mut checker = AccessibilityChecker::new("test");
+    checker
+        .run(&paragraph(content), &Settings::default(), &mut vec![])
+        .expect("checking accessibility failed!");
+    checker.diagnostics.into_iter().map(|d| d.kind).collect()
+}
+
+fn table(header: bool) -> Element {
+    let cell = |content: &str| {
+        Element::TableCell(TableCell {
+            position: Span::any(),
+            header,
+            attributes: vec![],
+            content: vec![text(content)],
+        })
+    };
+    Element::Table(Table {
+        position: Span::any(),
+        attributes: vec![],
+        caption: vec![],
+        caption_attributes: vec![],
+        rows: vec![Element::TableRow(TableRow {
+            position: Span::any(),
+            attributes: vec![],
+            cells: vec![cell("a"), cell("b")],
+        })],
+    })
+}
+
+fn span(style: &str, content: Vec<Element>) -> Element {
+    html_tag("span", &[("style", style)], content)
+}
+
+fn formatted(markup: MarkupType, content: Vec<Element>) -> Element {
+    Element::Formatted(Formatted {
+        position: Span::any(),
+        markup,
+        content,
+    })
+}
+
+#[test]
+fn url_scheme_and_host() {
+    assert_eq!(
+        split_url("HTTPS://User@De.Wikibooks.org:443/wiki"),
+        parts("https", "de.wikibooks.org")
+    );
+    assert_eq!(
+        split_url("mailto:info@Serlo.org"),
+        parts("mailto", "serlo.org")
+    );
+}
+
+#[test]
+fn protocol_relative_url() {
+    assert_eq!(
+        split_url("//upload.wikimedia.org/math.svg"),
+        parts("", "upload.wikimedia.org")
+    );
+}
+
+#[test]
+fn invalid_urls() {
+    assert!(split_url("de.wikibooks.org/wiki").is_err());
+    assert!(split_url("javascript://alert").is_err());
+    assert!(split_url("https:/de.wikibooks.org").is_err());
+    assert!(split_url("///path").is_err());
+}
+
+#[test]
+fn images_need_alt_or_caption() {
+    assert_eq!(
+        accessibility(vec![internal_reference(
+            "Datei:Bild.png",
+            &["mini"],
+            vec![]
+        )]),
+        vec!["image-without-alt"]
+    );
+    assert_eq!(
+        accessibility(vec![internal_reference(
+            "Datei:Bild.png",
+            &["alt= "],
+            vec![]
+        )]),
+        vec!["image-without-alt"]
+    );
+    assert!(accessibility(vec![internal_reference(
+        "Datei:Bild.png",
+        &["mini", "alt=Graph der Sinusfunktion"],
+        vec![],
+    )])
+    .is_empty());
+    assert!(accessibility(vec![internal_reference(
+        "Datei:Bild.png",
+        &["mini"],
+        vec![text("Graph der Sinusfunktion")],
+    )])
+    .is_empty());
+    // links to articles are no images
+    assert!(accessibility(vec![internal_reference("Folgen", &[], vec![])]).is_empty());
+}
+
+#[test]
+fn gallery_images_need_alt_or_caption() {
+    let gallery = Element::Gallery(Gallery {
+        position: Span::any(),
+        attributes: vec![],
+        content: vec![
+            internal_reference("Datei:Eins.png", &[], vec![text("Eins")]),
+            internal_reference("Datei:Zwei.png", &[], vec![]),
+        ],
+    });
+    let mut checker = AccessibilityChecker::new("test");
+    checker
+        .run(&gallery, &Settings::default(), &mut vec![])
+        .expect("checking accessibility failed!");
+    assert_eq!(checker.diagnostics.len(), 1);
+    assert!(checker.diagnostics[0]
+        .message
+        .starts_with("gallery image \"Datei:Zwei.png\""));
+    assert_eq!(
+        (
+            checker.summary.figures,
+            checker.summary.inaccessible_figures
+        ),
+        (2, 1)
+    );
+}
+
+#[test]
+fn tables_need_header_cells() {
+    assert_eq!(
+        accessibility(vec![table(false)]),
+        vec!["table-without-header"]
+    );
+    assert!(accessibility(vec![table(true)]).is_empty());
+}
+
+#[test]
+fn color_only_formatting() {
+    assert_eq!(
+        accessibility(vec![span("color: red", vec![text("wichtig")])]),
+        vec!["color-only-formatting"]
+    );
+    assert_eq!(
+        accessibility(vec![html_tag(
+            "font",
+            &[("color", "red")],
+            vec![text("wichtig")]
+        )]),
+        vec!["color-only-formatting"]
+    );
+    assert!(accessibility(vec![span("background-color: #eee", vec![text("x")])]).is_empty());
+    assert!(accessibility(vec![span("border: 1px solid", vec![text("x")])]).is_empty());
+}
+
+#[test]
+fn color_with_other_formatting() {
+    let bold = formatted(MarkupType::Bold, vec![text("wichtig")]);
+    assert!(accessibility(vec![span("color: red", vec![bold])]).is_empty());
+    assert!(accessibility(vec![formatted(
+        MarkupType::Italic,
+        vec![span("color:red", vec![text("wichtig")])],
+    )])
+    .is_empty());
+    assert!(accessibility(vec![span("color: red; font-weight: bold", vec![text("x")])]).is_empty());
+    assert!(accessibility(vec![html_tag(
+        "strong",
+        &[],
+        vec![span("color: red", vec![text("wichtig")])],
+    )])
+    .is_empty());
+    assert_eq!(
+        accessibility(vec![span(
+            "color: red; font-weight: normal",
+            vec![text("x")]
+        )]),
+        vec!["color-only-formatting"]
+    );
+}
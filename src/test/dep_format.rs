@@ -0,0 +1,79 @@
+use crate::deps::format::*;
+
+fn manifest_output(format: DepFormat) -> String {
+    let dependencies = vec!["sections/A/one.json".to_string(), "config.yml".to_string()];
+    let inputs = vec!["config.yml".to_string(), "theme.tex".to_string()];
+    let manifest = DepManifest {
+        target: "out/article.tex",
+        kind: "sections",
+        comment: "sections of article".to_string(),
+        dependencies: &dependencies,
+        inputs: &inputs,
+    };
+    let mut out = vec![];
+    write_dependencies(format, &manifest, &mut out).expect("could not write dependencies!");
+    String::from_utf8(out).expect("dependency output is not utf-8!")
+}
+
+#[test]
+fn escape_ninja_paths() {
+    assert_eq!(escape_ninja("plain/path.tex"), "plain/path.tex");
+    assert_eq!(escape_ninja("with space.tex"), "with\\ space.tex");
+    assert_eq!(escape_ninja("issue#1.tex"), "issue\\#1.tex");
+    assert_eq!(escape_ninja("cost$.tex"), "cost$$.tex");
+    assert_eq!(escape_ninja("c:/media.png"), "c:/media.png");
+}
+
+#[test]
+fn escape_make_paths() {
+    assert_eq!(escape_make("plain/path.tex"), "plain/path.tex");
+    assert_eq!(escape_make("with space.tex"), "with\\ space.tex");
+    assert_eq!(escape_make("issue#1.tex"), "issue\\#1.tex");
+    assert_eq!(escape_make("cost$.tex"), "cost$$.tex");
+    assert_eq!(escape_make("c:/media.png"), "c\\:/media.png");
+}
+
+#[test]
+fn escape_backslashes() {
+    // backslashes are only special before an escaped character
+    assert_eq!(escape_ninja("dir\\file.tex"), "dir\\file.tex");
+    assert_eq!(escape_ninja("dir\\ file.tex"), "dir\\\\\\ file.tex");
+    assert_eq!(escape_make("dir\\:file.tex"), "dir\\\\\\:file.tex");
+    assert_eq!(escape_make("dir\\\\#1.tex"), "dir\\\\\\\\\\#1.tex");
+}
+
+#[test]
+fn make_output() {
+    assert_eq!(
+        manifest_output(DepFormat::Make),
+        "# sections of article\n\
+         out/article.tex: \\\n\
+         \tsections/A/one.json\\\n\
+         \tconfig.yml\\\n\
+         \ttheme.tex\n"
+    );
+}
+
+#[test]
+fn ninja_output() {
+    assert_eq!(
+        manifest_output(DepFormat::Ninja),
+        "out/article.tex: sections/A/one.json config.yml theme.tex\n"
+    );
+}
+
+#[test]
+fn json_output() {
+    let output = manifest_output(DepFormat::Json);
+    assert!(output.ends_with('\n'));
+    let json: serde_json::Value = serde_json::from_str(&output).expect("invalid json manifest!");
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "target": "out/article.tex",
+            "kind": "sections",
+            "dependencies": ["sections/A/one.json", "config.yml"],
+            "inputs": ["config.yml", "theme.tex"],
+        })
+    );
+}
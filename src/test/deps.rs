@@ -0,0 +1,80 @@
+use super::util::{document, template};
+use crate::deps::transitive_sections;
+use crate::preamble::*;
+use std::fs;
+use std::path::PathBuf;
+
+fn inclusion(article: &str, section: &str) -> Element {
+    template(
+        &format!("{}{}", SECTION_INCLUSION_PREFIX, article),
+        &[("1", section)],
+    )
+}
+
+/// An empty section store in the temporary directory.
+fn section_store(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("mfnf-test-{}", name));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("could not create section store!");
+    path
+}
+
+fn write_section(store: &PathBuf, article: &str, section: &str, content: Vec<Element>) {
+    let path = PathBuf::from(get_section_path(article, section, store));
+    fs::create_dir_all(path.parent().unwrap()).expect("could not create section directory!");
+    let file = fs::File::create(&path).expect("could not create section!");
+    serde_json::to_writer(&file, &content).expect("could not write section!");
+}
+
+#[test]
+fn indirect_inclusions() {
+    let store = section_store("indirect-inclusions");
+    write_section(&store, "A", "one", vec![inclusion("B", "two")]);
+    write_section(&store, "B", "two", vec![]);
+
+    let root = document(vec![inclusion("A", "one")]);
+    let sections = transitive_sections(&root, &store).expect("collecting sections failed!");
+    assert_eq!(
+        sections,
+        vec![
+            get_section_path("A", "one", &store),
+            get_section_path("B", "two", &store),
+        ]
+    );
+}
+
+#[test]
+fn inclusion_cycles() {
+    let store = section_store("inclusion-cycles");
+    write_section(&store, "A", "one", vec![inclusion("B", "two")]);
+    write_section(&store, "B", "two", vec![inclusion("A", "one")]);
+
+    let root = document(vec![inclusion("A", "one"), inclusion("B", "two")]);
+    let sections = transitive_sections(&root, &store).expect("collecting sections failed!");
+    assert_eq!(
+        sections,
+        vec![
+            get_section_path("A", "one", &store),
+            get_section_path("B", "two", &store),
+        ]
+    );
+}
+
+#[test]
+fn missing_and_unreadable_sections_are_listed() {
+    let store = section_store("missing-sections");
+    write_section(&store, "A", "one", vec![inclusion("C", "missing")]);
+    // a directory in place of the section file cannot be parsed
+    fs::create_dir_all(get_section_path("B", "broken", &store)).unwrap();
+
+    let root = document(vec![inclusion("A", "one"), inclusion("B", "broken")]);
+    let sections = transitive_sections(&root, &store).expect("collecting sections failed!");
+    assert_eq!(
+        sections,
+        vec![
+            get_section_path("A", "one", &store),
+            get_section_path("B", "broken", &store),
+            get_section_path("C", "missing", &store),
+        ]
+    );
+}
@@ -0,0 +1,39 @@
+use super::util::span;
+use crate::diagnostics::{has_errors, write_diagnostics, Diagnostic, Severity};
+
+fn diagnostic(severity: Severity, line: usize) -> Diagnostic {
+    let message = format!("problem in line {}", line);
+    Diagnostic::new(severity, "some-check", message, "Doc", &span(line))
+}
+
+#[test]
+fn diagnostics_are_written_one_per_line() {
+    let diagnostics = vec![
+        diagnostic(Severity::Warning, 2),
+        diagnostic(Severity::Error, 7),
+    ];
+    let mut out = vec![];
+    write_diagnostics(&diagnostics, &mut out).expect("could not write diagnostics!");
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        format!(
+            "Doc:2:1: {}[some-check]: problem in line 2\n\
+             Doc:7:1: {}[some-check]: problem in line 7\n",
+            Severity::Warning,
+            Severity::Error
+        )
+    );
+}
+
+#[test]
+fn only_errors_are_errors() {
+    assert!(!has_errors(&[]));
+    assert!(!has_errors(&[
+        diagnostic(Severity::Info, 1),
+        diagnostic(Severity::Warning, 2)
+    ]));
+    assert!(has_errors(&[
+        diagnostic(Severity::Warning, 1),
+        diagnostic(Severity::Error, 2)
+    ]));
+}
@@ -0,0 +1,88 @@
+use crate::footnotes::Footnotes;
+use crate::preamble::*;
+use serde_yaml;
+
+/// A named reference `x`, defined in the included articles A and B
+/// and reused in A again.
+const SCOPED: &str = "
+type: paragraph
+position: {}
+content:
+    - type: comment
+      position: {}
+      text: \"included from: A|s\"
+    - type: htmltag
+      position: {}
+      name: ref
+      attributes:
+        - position: {}
+          key: name
+          value: x
+      content:
+        - type: text
+          position: {}
+          text: from A
+    - type: comment
+      position: {}
+      text: \"end of inclusion: A|s\"
+    - type: comment
+      position: {}
+      text: \"included from: B|s\"
+    - type: htmltag
+      position: {}
+      name: ref
+      attributes:
+        - position: {}
+          key: name
+          value: x
+      content:
+        - type: text
+          position: {}
+          text: from B
+    - type: comment
+      position: {}
+      text: \"end of inclusion: B|s\"
+    - type: comment
+      position: {}
+      text: \"included from: A|t\"
+    - type: htmltag
+      position: {}
+      name: ref
+      attributes:
+        - position: {}
+          key: name
+          value: x
+      content: []
+    - type: comment
+      position: {}
+      text: \"end of inclusion: A|t\"";
+
+#[test]
+fn reference_names_are_scoped_by_article() {
+    let root: Element = serde_yaml::from_str(SCOPED).expect("could not parse test input!");
+    let content = match root {
+        Element::Paragraph(ref paragraph) => &paragraph.content,
+        _ => panic!("expected a paragraph!"),
+    };
+    let mut footnotes = Footnotes::new(&root);
+    let mut citations = vec![];
+    for element in content {
+        match element {
+            Element::Comment(ref comment) => footnotes.comment(comment),
+            Element::HtmlTag(ref tag) => citations.push(footnotes.cite(tag)),
+            _ => (),
+        }
+    }
+    let summary: Vec<_> = citations
+        .iter()
+        .map(|c| (c.id, c.use_index, c.content.map(|c| extract_plain_text(c))))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (0, 1, Some("from A".to_string())),
+            (1, 1, Some("from B".to_string())),
+            (0, 2, Some("from A".to_string())),
+        ]
+    );
+}
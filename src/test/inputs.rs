@@ -0,0 +1,43 @@
+use crate::inputs::{read_inputs, record_input, recorded_inputs, write_inputs};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn inputs_are_recorded_once_in_order() {
+    // the recorder is thread-local and every test runs in its own thread
+    record_input("config.yml");
+    record_input("sections/A/one.json");
+    record_input(PathBuf::from("config.yml"));
+    record_input("theme.tex");
+    assert_eq!(
+        recorded_inputs(),
+        vec![
+            PathBuf::from("config.yml"),
+            PathBuf::from("sections/A/one.json"),
+            PathBuf::from("theme.tex"),
+        ]
+    );
+}
+
+#[test]
+fn inputs_file_round_trip() {
+    record_input("config.yml");
+    record_input("with space/markers.json");
+
+    let path = std::env::temp_dir().join("mfnf-test-inputs-file");
+    write_inputs(&mut fs::File::create(&path).unwrap()).expect("could not write inputs!");
+    assert_eq!(
+        read_inputs(&path).expect("could not read inputs!"),
+        vec!["config.yml", "with space/markers.json"]
+    );
+}
+
+#[test]
+fn empty_lines_are_no_inputs() {
+    let path = std::env::temp_dir().join("mfnf-test-empty-inputs");
+    fs::write(&path, "config.yml\n\n  \ntheme.tex\n").unwrap();
+    assert_eq!(
+        read_inputs(&path).expect("could not read inputs!"),
+        vec!["config.yml", "theme.tex"]
+    );
+}
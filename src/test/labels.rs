@@ -0,0 +1,41 @@
+use crate::labels::*;
+
+#[test]
+fn readable_labels() {
+    assert_eq!(
+        anchor_slug("Mathe_f%C3%BCr_Nicht-Freaks:_Folgen#Anker:.C3.9Cbersicht"),
+        "mathe-fuer-nicht-freaks:folgen:anker:uebersicht"
+    );
+}
+
+#[test]
+fn label_collisions() {
+    let anchors = vec!["Doc#a_b".to_string(), "Doc#a-b".to_string()];
+    let labels = LabelMap::new(anchors.iter());
+    assert_eq!(labels.label("Doc#a-b"), "doc:a-b-9e4add3a");
+    assert_eq!(labels.label("Doc#a_b"), "doc:a-b-87173ad0");
+}
+
+#[test]
+fn labels_do_not_depend_on_other_anchors() {
+    let anchors = vec![
+        "Doc#a_b".to_string(),
+        "Doc#a-b".to_string(),
+        "Doc#0".to_string(),
+        // another anchor with the same slug
+        "Doc#a.20b".to_string(),
+    ];
+    let labels = LabelMap::new(anchors.iter());
+    assert_eq!(labels.label("Doc#a-b"), "doc:a-b-9e4add3a");
+    assert_eq!(labels.label("Doc#a_b"), "doc:a-b-87173ad0");
+    assert_eq!(labels.label("Doc#0"), "doc:0");
+}
+
+#[test]
+fn unknown_anchors_do_not_collide() {
+    let anchors = vec!["Doc#a_b".to_string()];
+    let labels = LabelMap::new(anchors.iter());
+    assert_eq!(labels.label("Doc#a_b"), "doc:a-b");
+    assert_eq!(labels.label("Doc#a b"), "doc:a-b-98579fbb");
+    assert_eq!(labels.label("Doc#c"), "doc:c");
+}
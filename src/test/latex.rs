@@ -1,7 +1,13 @@
-use crate::latex::LatexArgs;
+use super::util::{spec_template, text};
+use crate::latex::{
+    bib_key, check_snippet, escape_index, extract_source_map, index_sort_key, mark_origin,
+    BibEntry, LatexArgs, LatexStrings, LatexTarget, LatexTheme, SourceOrigin, SNIPPETS,
+};
 use crate::preamble::*;
 use crate::Targets;
+use mfnf_template_spec::KnownTemplate;
 use serde_yaml;
+use std::collections::HashMap;
 use structopt::StructOpt;
 
 macro_rules! test_case {
@@ -74,6 +80,42 @@ content:
 "
 );
 
+test_case!(
+    Targets::Latex,
+    reused_footnote,
+    "
+type: paragraph
+position: {}
+content:
+    - type: text
+      position: {}
+      text: cited
+    - type: htmltag
+      position: {}
+      name: ref
+      attributes:
+        - position: {}
+          key: name
+          value: source
+      content:
+        - type: text
+          position: {}
+          text: a source
+    - type: text
+      position: {}
+      text: \" and again\"
+    - type: htmltag
+      position: {}
+      name: ref
+      attributes:
+        - position: {}
+          key: name
+          value: source
+      content: []",
+    "cited\\footnote{a source\\label{test-doc.note-0}} and again\\footref{test-doc.note-0}
+"
+);
+
 test_case!(
     Targets::Latex,
     italic_text,
@@ -132,7 +174,7 @@ content:
       position: {}
       text: some text",
     "\\section{heading caption}
-    \\label{dGVzdF9kb2MjaGVhZGluZ19jYXB0aW9u}
+    \\label{test-doc:heading-caption}
 
     some text
 "
@@ -188,3 +230,224 @@ content:
 \\end{enumerate}
 "
 );
+
+#[test]
+fn default_theme_is_valid() {
+    for snippet in SNIPPETS {
+        assert_eq!(check_snippet(snippet.name, snippet.default), Ok(()));
+    }
+}
+
+#[test]
+fn theme_override_checks_placeholders() {
+    assert!(check_snippet("bold", "\\textbf{<%content%>}").is_ok());
+    assert!(check_snippet("bold", "\\textbf{text}").is_err());
+    assert!(check_snippet("bold", "\\textbf{<%contents%>}").is_err());
+    assert!(check_snippet("bolt", "\\textbf{<%content%>}").is_err());
+}
+
+#[test]
+fn former_snippet_names_are_accepted() {
+    let theme: LatexTheme = serde_yaml::from_str("html_ref: \"\\\\footnote{<%content%>}\"")
+        .expect("could not parse theme!");
+    assert_eq!(theme.get("footnote"), "\\footnote{<%content%>}");
+}
+
+#[test]
+fn placeholders_are_filled() {
+    let mut values = HashMap::new();
+    values.insert("title", "Folgen".to_string());
+    values.insert("body", "<%title%>".to_string());
+    assert_eq!(
+        fill_placeholders("\\title{<%title%>}\n<%body%>", &values),
+        Ok("\\title{Folgen}\n<%title%>".to_string())
+    );
+}
+
+#[test]
+fn placeholders_do_not_collide_with_latex() {
+    let values = HashMap::new();
+    let source = "\\def\\x{{foo}} 50% <% x %> <%Name%> <%%>";
+    assert_eq!(placeholders(source), Vec::<&str>::new());
+    assert_eq!(fill_placeholders(source, &values), Ok(source.to_string()));
+}
+
+#[test]
+fn missing_placeholder_values() {
+    let mut values = HashMap::new();
+    values.insert("body", String::new());
+    assert_eq!(
+        fill_placeholders("<%title%><%body%><%packages%>", &values),
+        Err(vec!["title".to_string(), "packages".to_string()])
+    );
+}
+
+#[test]
+fn induction_intro_is_split_at_set() {
+    let strings = LatexStrings::default();
+    assert_eq!(
+        strings.induction_intro(),
+        (
+            "Aussageform, deren Allgemeingültigkeit für ".to_string(),
+            Some(" bewiesen werden soll:".to_string())
+        )
+    );
+}
+
+#[test]
+fn induction_intro_without_set() {
+    let strings: LatexStrings = serde_yaml::from_str("induction_intro: \"Statement & claim:\"")
+        .expect("could not parse strings!");
+    assert_eq!(
+        strings.induction_intro(),
+        ("Statement \\& claim:".to_string(), None)
+    );
+    let strings: LatexStrings =
+        serde_yaml::from_str("induction_intro: \"For all <%set%>, 100% sure:\"")
+            .expect("could not parse strings!");
+    assert_eq!(
+        strings.induction_intro(),
+        ("For all ".to_string(), Some(", 100\\% sure:".to_string()))
+    );
+}
+
+#[test]
+fn caption_macros_are_escaped() {
+    let strings: LatexStrings =
+        serde_yaml::from_str("question_caption: Q&A").expect("could not parse strings!");
+    let macros = strings.caption_macros();
+    assert!(macros.contains("\\def\\questioncaption{Q\\&A}"));
+    assert!(macros.contains("\\def\\summaryenvcaption{Zusammenfassung}"));
+}
+
+#[test]
+fn wrap_keeps_math_and_arguments_together() {
+    let input = "aaaa $x + y$ \\href{http://example.org/a b}{link text} bbbb";
+    assert_eq!(
+        indent_and_trim(input, 0, 10),
+        "aaaa \n$x + y$ \n\\href{http://example.org/a b}{link text} \nbbbb "
+    );
+}
+
+#[test]
+fn wrap_keeps_comments_safe() {
+    let input = "text \\% more % comment text";
+    assert_eq!(
+        indent_and_trim(input, 0, 10),
+        "text \\% \nmore % \n% comment \n% text "
+    );
+}
+
+#[test]
+fn wrap_skips_verbatim() {
+    let input = "\\begin{verbatim}\n   a b c d e f g h\n\\end{verbatim}";
+    assert_eq!(
+        indent_and_trim(input, 2, 5),
+        "  \\begin{verbatim} \n   a b c d e f g h\n\\end{verbatim}"
+    );
+}
+
+#[test]
+fn source_map_maps_lines_to_innermost_origin() {
+    let origin = |document: &str| SourceOrigin {
+        document: document.into(),
+        position: Span::any(),
+    };
+    let origins = vec![origin("outer"), origin("inner")];
+    let inner = mark_origin("inner line\n", 1);
+    let output = mark_origin(&format!("\nfirst\n{}last\n", inner), 0);
+
+    let (clean, map) = extract_source_map(&output, &origins);
+    assert_eq!(clean, "\nfirst\ninner line\nlast\n");
+    let ranges: Vec<_> = map
+        .iter()
+        .map(|e| (e.start_line, e.end_line, e.document.as_str()))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![(2, 2, "outer"), (3, 3, "inner"), (4, 4, "outer")]
+    );
+}
+
+#[test]
+fn bibliography_keys_do_not_depend_on_author_format() {
+    let title = "Analysis 1: Differential- und Integralrechnung";
+    let key = bib_key(Some("Otto Forster"), Some("2016"), title);
+    assert_eq!(key, "forster:2016:analysis-1-differential");
+    assert_eq!(bib_key(Some("Forster, Otto"), Some("2016"), title), key);
+}
+
+#[test]
+fn bibliography_keys_tell_similar_works_apart() {
+    let work = |title: &str| BibEntry {
+        key: bib_key(Some("Otto Forster"), Some("2016"), title),
+        author: Some("Otto Forster".into()),
+        title: title.into(),
+        publisher: None,
+        address: None,
+        year: Some("2016".into()),
+        isbn: None,
+    };
+    let first = work("Analysis 1: Differential- und Integralrechnung");
+    let second = work("Analysis 1: Differential- und Integralrechnung einer Veränderlichen");
+    assert_eq!(first.key, second.key);
+    assert_ne!(first.fields_hash(), second.fields_hash());
+    assert_eq!(first.fields_hash().len(), 8);
+}
+
+#[test]
+fn index_entries_are_sorted_and_quoted() {
+    assert_eq!(index_sort_key("Äquivalenz-Relation"), "aquivalenz relation");
+    assert_eq!(escape_index("$|x|$"), "$\"|x\"|$");
+    assert_eq!(escape_index("Fl\\\"ache"), "Fl\\\"ache");
+    assert_eq!(escape_index("$\\|x\\|$"), "$\"\\\"|x\"\\\"|$");
+}
+
+/// Render a document with index entries enabled.
+fn render_indexed(root: &Element) -> String {
+    let target: LatexTarget = serde_yaml::from_str("index: true").expect("invalid target!");
+    let args = LatexArgs::from_iter(["test", "test_doc", "src/test/test.anchors"].iter());
+    let mut res = vec![];
+    target
+        .export(root, &Settings::default(), &args, &mut res)
+        .expect("export failed!");
+    String::from_utf8_lossy(&res).to_string()
+}
+
+#[test]
+fn definitions_are_indexed() {
+    let root = serde_yaml::from_str(
+        "
+type: htmltag
+position: {}
+name: dfn
+attributes: []
+content:
+    - type: text
+      position: {}
+      text: Grenzwert",
+    )
+    .expect("could not parse test input!");
+    assert_eq!(
+        render_indexed(&root),
+        "\\textit{Grenzwert}\\index{grenzwert@Grenzwert}"
+    );
+}
+
+#[test]
+fn definition_titles_are_indexed() {
+    let title = vec![text("Äquivalenzrelation")];
+    let root = spec_template(
+        |t| match t {
+            KnownTemplate::Definition(_) => true,
+            _ => false,
+        },
+        &[("title", title)],
+    );
+    let output = render_indexed(&root);
+    assert!(
+        output.contains("\\index{aquivalenzrelation@Äquivalenzrelation}"),
+        "{}",
+        &output
+    );
+}
@@ -0,0 +1,66 @@
+use crate::latex_log::{parse_log, LogProblemKind};
+
+const LOG: &str = "\
+This is pdfTeX, Version 3.14159265-2.6-1.40.19 (TeX Live 2018) (preloaded format=pdflatex)
+! Undefined control sequence.
+l.42 \\foo
+          {bar}
+Overfull \\hbox (12.5pt too wide) in paragraph at lines 10--12
+LaTeX Warning: Reference `test-doc:heading' on page 3 undefined on input line 7.
+./book.tex:99: Missing $ inserted.
+";
+
+#[test]
+fn log_problems_are_extracted() {
+    let problems = parse_log(LOG);
+    let summary: Vec<_> = problems.iter().map(|p| (p.kind, p.line)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (LogProblemKind::Error, Some(42)),
+            (LogProblemKind::OverfullBox, Some(10)),
+            (LogProblemKind::UndefinedReference, Some(7)),
+            (LogProblemKind::Error, Some(99)),
+        ]
+    );
+    assert_eq!(problems[0].message, "Undefined control sequence.");
+    assert_eq!(problems[1].overfull_pt, Some(12.5));
+}
+
+const NESTED_LOG: &str = "\
+(./book.tex (/usr/share/texlive/amsmath.sty
+./x.sty:5: Undefined control sequence.
+! Package amsmath Error: Old form `\\pmatrix' should be \\begin{pmatrix}.
+l.3 \\pmatrix
+) (./chapter.tex
+LaTeX Warning: Citation `forster:2016' on page 2 undefined on input line 4.
+)
+Overfull \\hbox (3.0pt too wide) in paragraph at lines 20--21
+)
+";
+
+#[test]
+fn log_problems_are_located_in_their_file() {
+    let problems = parse_log(NESTED_LOG);
+    let summary: Vec<_> = problems
+        .iter()
+        .map(|p| (p.kind, p.file.as_ref().map(|f| f.as_str()), p.line))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (LogProblemKind::Error, Some("./x.sty"), Some(5)),
+            (
+                LogProblemKind::Error,
+                Some("/usr/share/texlive/amsmath.sty"),
+                Some(3)
+            ),
+            (
+                LogProblemKind::UndefinedCitation,
+                Some("./chapter.tex"),
+                Some(4)
+            ),
+            (LogProblemKind::OverfullBox, Some("./book.tex"), Some(20)),
+        ]
+    );
+}
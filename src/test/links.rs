@@ -0,0 +1,57 @@
+use crate::links::{dot_string, write_graph, ArticleLink, GraphFormat};
+
+fn edges() -> Vec<ArticleLink> {
+    vec![
+        ArticleLink {
+            source: "Folgen".to_string(),
+            target: "Reihen".to_string(),
+            count: 2,
+        },
+        ArticleLink {
+            source: "Zitat \"Satz\"".to_string(),
+            target: "C:\\Pfad".to_string(),
+            count: 1,
+        },
+    ]
+}
+
+fn graph(format: GraphFormat) -> String {
+    let mut out = vec![];
+    write_graph(format, &edges(), &mut out).expect("could not write graph!");
+    String::from_utf8(out).expect("graph is not utf-8!")
+}
+
+#[test]
+fn dot_strings_are_quoted() {
+    assert_eq!(dot_string("Folgen"), "\"Folgen\"");
+    assert_eq!(
+        dot_string("Mathe für Nicht-Freaks"),
+        "\"Mathe für Nicht-Freaks\""
+    );
+    assert_eq!(dot_string("Zitat \"Satz\""), "\"Zitat \\\"Satz\\\"\"");
+    assert_eq!(dot_string("C:\\Pfad\\"), "\"C:\\\\Pfad\\\\\"");
+}
+
+#[test]
+fn dot_graph() {
+    assert_eq!(
+        graph(GraphFormat::Dot),
+        "digraph links {\n    \
+         \"Folgen\" -> \"Reihen\" [weight=2];\n    \
+         \"Zitat \\\"Satz\\\"\" -> \"C:\\\\Pfad\" [weight=1];\n\
+         }\n"
+    );
+}
+
+#[test]
+fn json_graph() {
+    let json: serde_json::Value =
+        serde_json::from_str(&graph(GraphFormat::Json)).expect("invalid json graph!");
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"source": "Folgen", "target": "Reihen", "count": 2},
+            {"source": "Zitat \"Satz\"", "target": "C:\\Pfad", "count": 1},
+        ])
+    );
+}
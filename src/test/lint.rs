@@ -0,0 +1,59 @@
+use super::util::template;
+use crate::diagnostics::Diagnostic;
+use crate::lint::LintTarget;
+use crate::preamble::*;
+use mfnf_template_spec::{spec, Priority};
+
+fn validate(root: &Element) -> Vec<Diagnostic> {
+    LintTarget::only(&["template-spec"])
+        .lint(root, &Settings::default(), "test")
+        .expect("linting failed!")
+}
+
+#[test]
+fn unknown_template() {
+    let diagnostics = validate(&template("Gibt es nicht", &[]));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, "template-spec");
+    assert!(diagnostics[0]
+        .message
+        .starts_with("unknown template \"gibt es nicht\"."));
+}
+
+#[test]
+fn parser_functions_are_no_templates() {
+    assert!(validate(&template("#lst:Artikel", &[("1", "Abschnitt")])).is_empty());
+    assert!(validate(&template("Index", &[("1", "Stetigkeit")])).is_empty());
+}
+
+#[test]
+fn argument_names_ignore_case_and_whitespace() {
+    let specs = spec();
+    let (template_spec, attribute) = specs
+        .iter()
+        .flat_map(|s| s.attributes.iter().map(move |a| (s, a)))
+        .find(|(_, a)| {
+            if let Priority::Required = a.priority {
+                true
+            } else {
+                false
+            }
+        })
+        .expect("no template with required arguments!");
+
+    let name = format!(" {} ", attribute.names[0].trim().to_uppercase());
+    let root = template(&template_spec.names[0], &[(name.as_str(), "x")]);
+    for diagnostic in validate(&root) {
+        assert!(
+            !diagnostic.message.starts_with("unknown argument"),
+            "{}",
+            &diagnostic.message
+        );
+        let missing = format!("required argument {:?} ", &attribute.default_name);
+        assert!(
+            !diagnostic.message.contains(&missing),
+            "{}",
+            &diagnostic.message
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use super::util::{document, internal_reference, span};
+use crate::deps::check::check_media;
+use crate::deps::printers::{FilesPrinter, MediaDependency};
+use crate::meta::{MediaLicense, MediaMeta};
+use crate::preamble::*;
+use std::fs;
+use std::path::PathBuf;
+
+fn reference(target: &str, line: usize) -> Element {
+    let mut reference = internal_reference(target, &[], vec![]);
+    if let Element::InternalReference(ref mut iref) = reference {
+        iref.position = span(line);
+    }
+    reference
+}
+
+/// A media dependency with paths in an empty temporary directory.
+fn dependency(name: &str) -> MediaDependency {
+    let dir = std::env::temp_dir().join(format!("mfnf-test-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("could not create media directory!");
+    MediaDependency {
+        mapped_path: dir.join("image.pdf"),
+        source_path: dir.join("image.png"),
+        meta_path: dir.join("image.png.meta"),
+        position: span(3),
+    }
+}
+
+fn write_meta(path: &PathBuf) {
+    let meta = MediaMeta {
+        license: MediaLicense {
+            user: "user".to_string(),
+            name: "CC BY-SA 4.0".to_string(),
+            shortname: "CC BY-SA".to_string(),
+            licenseurl: "https://creativecommons.org/licenses/by-sa/4.0".to_string(),
+            url: "https://commons.wikimedia.org/wiki/File:image.png".to_string(),
+            authors: vec!["author".to_string()],
+            source: String::new(),
+            detailsurl: String::new(),
+            filename: "image.png".to_string(),
+        },
+    };
+    let file = fs::File::create(path).expect("could not create meta data!");
+    serde_json::to_writer(&file, &meta).expect("could not write meta data!");
+}
+
+fn kinds(diagnostics: &[crate::diagnostics::Diagnostic]) -> Vec<&str> {
+    diagnostics.iter().map(|d| d.kind.as_str()).collect()
+}
+
+#[test]
+fn present_media_is_fine() {
+    let media = dependency("present-media");
+    fs::write(&media.source_path, b"png").unwrap();
+    write_meta(&media.meta_path);
+    assert!(check_media(&media, "Doc").is_empty());
+}
+
+#[test]
+fn missing_media_and_meta() {
+    let media = dependency("missing-media");
+    let diagnostics = check_media(&media, "Doc");
+    assert_eq!(kinds(&diagnostics), vec!["missing-media", "missing-meta"]);
+    assert!(diagnostics.iter().all(|d| d.document == "Doc"));
+    assert!(diagnostics.iter().all(|d| d.position == media.position));
+}
+
+#[test]
+fn unreadable_meta() {
+    let media = dependency("unreadable-meta");
+    fs::write(&media.source_path, b"png").unwrap();
+    fs::write(&media.meta_path, b"{ \"license\": null }").unwrap();
+    assert_eq!(kinds(&check_media(&media, "Doc")), vec!["unreadable-meta"]);
+}
+
+#[test]
+fn files_are_collected_once_per_source() {
+    let settings = Settings::default();
+    let root = document(vec![
+        reference("Datei:Bild.png", 1),
+        reference("Link", 2),
+        reference("Bild:Bild.png", 3),
+        reference("Datei:Anderes.svg", 4),
+        reference("Datei: Bild.png", 5),
+    ]);
+    let mut printer = FilesPrinter::new(TargetType::Latex);
+    printer
+        .run(&root, &settings, &mut vec![])
+        .expect("collecting files failed!");
+
+    let sources: Vec<_> = printer.files.iter().map(|f| &f.source_path).collect();
+    assert_eq!(
+        sources,
+        vec![
+            &settings.media_path.join("Bild.png"),
+            &settings.media_path.join("Anderes.svg"),
+        ]
+    );
+    // the first reference is kept
+    assert_eq!(printer.files[0].position, span(1));
+    assert_eq!(printer.dependencies().len(), 4);
+}
@@ -1 +1,19 @@
+mod anchor_index;
+mod anchors;
+mod audit;
+mod dep_format;
+mod deps;
+mod diagnostics;
+mod footnotes;
+mod inputs;
+mod labels;
 mod latex;
+mod latex_log;
+mod links;
+mod lint;
+mod media_deps;
+mod references;
+mod sections;
+mod stats;
+mod title;
+mod util;
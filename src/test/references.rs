@@ -0,0 +1,21 @@
+use crate::anchors::AnchorIndex;
+use crate::stats::suggest_anchors;
+
+#[test]
+fn suggestions_are_decoded() {
+    let anchors = AnchorIndex::parse(
+        "Folgen\nFolgen#.C3.9Cbersicht\nFolgen#Anker:Satz_vom_Maximum\nReihen#.C3.9Cbersicht\n",
+    )
+    .expect("could not parse anchors!");
+    assert_eq!(
+        suggest_anchors("Folgen#.C3.9Cbersichten", &anchors, 1),
+        vec!["Folgen#Übersicht"]
+    );
+}
+
+#[test]
+fn suggestions_compare_decoded_anchors() {
+    // the encoded anchors are too different to be suggested
+    let anchors = AnchorIndex::parse("Doc#.C3.84b\n").expect("could not parse anchors!");
+    assert_eq!(suggest_anchors("Doc#Ab", &anchors, 3), vec!["Doc#Äb"]);
+}
@@ -0,0 +1,82 @@
+use super::util::{document, html_tag, paragraph, text};
+use crate::preamble::*;
+use crate::{SectionsArgs, SectionsTarget};
+use structopt::StructOpt;
+
+fn tag(kind: &str, label: &str) -> Element {
+    html_tag("section", &[(kind, label)], vec![])
+}
+
+/// Concatenated text of the elements.
+fn texts(elements: &[Element]) -> String {
+    elements
+        .iter()
+        .map(|e| match e {
+            Element::Text(ref t) => t.text.clone(),
+            _ => texts(&extract_content(e.clone()).unwrap_or_default()),
+        })
+        .collect()
+}
+
+fn extract(root: &Element, label: &str) -> String {
+    let args = SectionsArgs::from_iter(["test", label].iter());
+    let mut out = vec![];
+    SectionsTarget::default()
+        .export(root, (), &args, &mut out)
+        .expect("export failed!");
+    let section: Vec<Element> = serde_json::from_slice(&out).expect("invalid section output!");
+    texts(&section)
+}
+
+#[test]
+fn nested_sections() {
+    let root = document(vec![
+        tag("begin", "a"),
+        text("1"),
+        tag("begin", "b"),
+        text("2"),
+        tag("end", "b"),
+        text("3"),
+        tag("end", "a"),
+    ]);
+    assert_eq!(extract(&root, "a"), "123");
+    assert_eq!(extract(&root, "b"), "2");
+}
+
+#[test]
+fn overlapping_sections() {
+    let root = document(vec![
+        tag("begin", "a"),
+        text("1"),
+        tag("begin", "b"),
+        text("2"),
+        tag("end", "a"),
+        text("3"),
+        tag("end", "b"),
+    ]);
+    assert_eq!(extract(&root, "a"), "12");
+    assert_eq!(extract(&root, "b"), "23");
+}
+
+#[test]
+fn repeated_sections_are_concatenated() {
+    let root = document(vec![
+        tag("begin", "a"),
+        text("1"),
+        tag("end", "a"),
+        text("x"),
+        tag("begin", "a"),
+        text("2"),
+        tag("end", "a"),
+    ]);
+    assert_eq!(extract(&root, "a"), "12");
+}
+
+#[test]
+fn section_tags_at_different_depths() {
+    let root = document(vec![
+        paragraph(vec![text("x"), tag("begin", "a"), text("1")]),
+        paragraph(vec![text("2"), tag("end", "a"), text("y")]),
+    ]);
+    assert_eq!(extract(&root, "a"), "12");
+}
@@ -0,0 +1,78 @@
+use super::util::{heading, paragraph, text};
+use crate::preamble::*;
+use crate::stats::SectionStats;
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.expect("no readability score!");
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "readability {} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn amstad_score() {
+    // 7 words, 2 sentences, 8 syllables
+    let content = vec![paragraph(vec![text("Das ist ein Satz. Und noch einer!")])];
+    let stats = SectionStats::new("test", 1, &content, &Settings::default());
+    assert_eq!((stats.words, stats.sentences, stats.syllables), (7, 2, 8));
+    assert_close(stats.readability, 180.0 - 3.5 - 58.5 * 8.0 / 7.0);
+}
+
+#[test]
+fn amstad_score_without_sentence_end() {
+    // an unterminated text counts as one sentence
+    let content = vec![paragraph(vec![text("Mathematik ist schön")])];
+    let stats = SectionStats::new("test", 1, &content, &Settings::default());
+    assert_eq!(stats.sentences, 0);
+    assert_close(stats.readability, 180.0 - 3.0 - 58.5 * 6.0 / 3.0);
+}
+
+#[test]
+fn amstad_score_of_empty_section() {
+    let stats = SectionStats::new("test", 1, &[], &Settings::default());
+    assert_eq!(stats.readability, None);
+}
+
+#[test]
+fn amstad_score_includes_subsections() {
+    let content = vec![
+        paragraph(vec![text("Das ist ein Satz.")]),
+        heading(2, "Teil", vec![paragraph(vec![text("Und noch einer!")])]),
+    ];
+    let stats = SectionStats::new("test", 1, &content, &Settings::default());
+    assert_eq!(stats.sections.len(), 1);
+    assert_close(
+        stats.sections[0].readability,
+        180.0 - 3.0 - 58.5 * 4.0 / 3.0,
+    );
+    assert_close(stats.readability, 180.0 - 3.5 - 58.5 * 8.0 / 7.0);
+}
+
+#[test]
+fn sentences_end_after_formulas_and_numbers() {
+    let formula = Element::Formatted(Formatted {
+        position: Span::any(),
+        markup: MarkupType::Math,
+        content: vec![text("a = b")],
+    });
+    let content = vec![paragraph(vec![
+        text("Es gilt die Gleichung "),
+        formula,
+        text(" . Das Ergebnis ist 42."),
+    ])];
+    let stats = SectionStats::new("test", 1, &content, &Settings::default());
+    assert_eq!(stats.inline_formulas, 1);
+    assert_eq!((stats.words, stats.sentences), (7, 2));
+}
+
+#[test]
+fn abbreviations_do_not_end_sentences() {
+    let content = vec![paragraph(vec![text(
+        "Das ist z. B. ein Satz, d. h. nur einer (vgl. unten) bzw. zwei. Oder u.a. drei?",
+    )])];
+    let stats = SectionStats::new("test", 1, &content, &Settings::default());
+    assert_eq!(stats.sentences, 2);
+}
@@ -0,0 +1,50 @@
+use crate::title::*;
+
+#[test]
+fn title_whitespace_and_case() {
+    assert_eq!(
+        normalize_title("mathe_für  Nicht-Freaks:_Folgen"),
+        "Mathe für Nicht-Freaks: Folgen"
+    );
+}
+
+#[test]
+fn title_percent_decoding() {
+    assert_eq!(normalize_title("Mathe%20f%C3%BCr"), "Mathe für");
+    assert_eq!(normalize_title("100%"), "100%");
+}
+
+#[test]
+fn title_namespace_alias() {
+    assert_eq!(normalize_title("bild:foo.png"), "Datei:Foo.png");
+    assert_eq!(normalize_title(":Kategorie: test"), "Kategorie:Test");
+}
+
+#[test]
+fn fragment_dot_encoding() {
+    assert_eq!(encode_fragment("Übersicht"), ".C3.9Cbersicht");
+    assert_eq!(
+        encode_fragment("Anker:Satz vom Maximum"),
+        "Anker:Satz_vom_Maximum"
+    );
+}
+
+#[test]
+fn target_without_title() {
+    assert_eq!(
+        target_anchor("#Abschnitt 1", "test doc"),
+        "Test_doc#Abschnitt_1"
+    );
+    assert_eq!(
+        target_anchor("test_doc#Abschnitt_1", "other"),
+        "Test_doc#Abschnitt_1"
+    );
+}
+
+#[test]
+fn readable_anchor() {
+    assert_eq!(
+        display_anchor("Mathe_f%C3%BCr_Nicht-Freaks:_Folgen#.C3.9Cbersicht_1"),
+        "Mathe für Nicht-Freaks: Folgen#Übersicht 1"
+    );
+}
@@ -0,0 +1,130 @@
+//! Builders for the syntax trees of tests.
+
+use crate::preamble::*;
+use mfnf_template_spec::{parse_template, spec, KnownTemplate};
+
+pub fn text(text: &str) -> Element {
+    Element::Text(Text {
+        position: Span::any(),
+        text: text.to_string(),
+    })
+}
+
+pub fn paragraph(content: Vec<Element>) -> Element {
+    Element::Paragraph(Paragraph {
+        position: Span::any(),
+        content,
+    })
+}
+
+pub fn document(content: Vec<Element>) -> Element {
+    Element::Document(Document {
+        position: Span::any(),
+        content,
+    })
+}
+
+/// A html tag with the given attributes as (key, value).
+pub fn html_tag(name: &str, attributes: &[(&str, &str)], content: Vec<Element>) -> Element {
+    Element::HtmlTag(HtmlTag {
+        position: Span::any(),
+        name: name.to_string(),
+        attributes: attributes
+            .iter()
+            .map(|(key, value)| TagAttribute {
+                position: Span::any(),
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect(),
+        content,
+    })
+}
+
+/// A template with the given arguments as (name, value).
+pub fn template(name: &str, arguments: &[(&str, &str)]) -> Element {
+    Element::Template(Template {
+        position: Span::any(),
+        name: vec![text(name)],
+        content: arguments
+            .iter()
+            .map(|(name, value)| {
+                Element::TemplateArgument(TemplateArgument {
+                    position: Span::any(),
+                    name: name.to_string(),
+                    value: vec![text(value)],
+                })
+            })
+            .collect(),
+    })
+}
+
+/// A span covering the start of the given line.
+pub fn span(line: usize) -> Span {
+    let position = Position {
+        offset: line * 10,
+        line,
+        col: 1,
+    };
+    Span {
+        start: position.clone(),
+        end: position,
+    }
+}
+
+/// A reference to `target` with the given options and caption.
+pub fn internal_reference(target: &str, options: &[&str], caption: Vec<Element>) -> Element {
+    Element::InternalReference(InternalReference {
+        position: Span::any(),
+        target: vec![text(target)],
+        options: options.iter().map(|option| vec![text(option)]).collect(),
+        caption,
+    })
+}
+
+pub fn heading(depth: usize, caption: &str, content: Vec<Element>) -> Element {
+    Element::Heading(Heading {
+        position: Span::any(),
+        depth,
+        caption: vec![text(caption)],
+        content,
+    })
+}
+
+/// The first specified template which `matches` when all its arguments
+/// are given. Arguments not in `arguments` (by default name) are `x`.
+pub fn spec_template<F>(matches: F, arguments: &[(&str, Vec<Element>)]) -> Element
+where
+    F: Fn(&KnownTemplate) -> bool,
+{
+    spec()
+        .iter()
+        .map(|s| {
+            let content = s
+                .attributes
+                .iter()
+                .map(|a| {
+                    let value = arguments
+                        .iter()
+                        .find(|(name, _)| *name == a.default_name)
+                        .map(|(_, value)| value.clone())
+                        .unwrap_or_else(|| vec![text("x")]);
+                    Element::TemplateArgument(TemplateArgument {
+                        position: Span::any(),
+                        name: a.names[0].to_string(),
+                        value,
+                    })
+                })
+                .collect();
+            Element::Template(Template {
+                position: Span::any(),
+                name: vec![text(&s.names[0])],
+                content,
+            })
+        })
+        .find(|t| match t {
+            Element::Template(ref t) => parse_template(t).map(|k| matches(&k)) == Some(true),
+            _ => false,
+        })
+        .expect("no matching template!")
+}
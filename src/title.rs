@@ -0,0 +1,184 @@
+//! Normalization of page titles and fragments following MediaWiki's rules.
+//!
+//! Link targets are normalized before they are compared to anchors, so that
+//! e.g. `mathe für Nicht-Freaks:_Folgen` and `Mathe für Nicht-Freaks: Folgen`
+//! resolve to the same anchor.
+
+use crate::util::urlencode;
+
+/// Known namespace names and aliases (lowercase) with their canonical name.
+const NAMESPACE_ALIASES: &[(&str, &str)] = &[
+    ("datei", "Datei"),
+    ("bild", "Datei"),
+    ("file", "Datei"),
+    ("image", "Datei"),
+    ("kategorie", "Kategorie"),
+    ("category", "Kategorie"),
+    ("vorlage", "Vorlage"),
+    ("template", "Vorlage"),
+    ("benutzer", "Benutzer"),
+    ("benutzerin", "Benutzer"),
+    ("user", "Benutzer"),
+    ("hilfe", "Hilfe"),
+    ("help", "Hilfe"),
+    ("wikibooks", "Wikibooks"),
+    ("project", "Wikibooks"),
+    ("spezial", "Spezial"),
+    ("special", "Spezial"),
+    ("diskussion", "Diskussion"),
+    ("talk", "Diskussion"),
+    ("mediawiki", "MediaWiki"),
+    ("regal", "Regal"),
+];
+
+/// Decode `%xx` sequences. Input which does not decode to valid UTF-8
+/// is returned unchanged.
+pub fn decode_percent(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let hex = String::from_utf8_lossy(&bytes[i + 1..i + 3]);
+            result.push(u8::from_str_radix(&hex, 16).expect("checked hex digits!"));
+            i += 3;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(result).unwrap_or_else(|_| input.to_string())
+}
+
+/// Decode MediaWiki's dot-encoding of fragments (`.C3.9C` -> `Ü`).
+/// Input which does not decode to valid UTF-8 is returned unchanged.
+pub fn decode_dots(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let is_hex = |b: u8| b.is_ascii_digit() || (b'A' <= b && b <= b'F');
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'.' && i + 2 < bytes.len() && is_hex(bytes[i + 1]) && is_hex(bytes[i + 2]) {
+            let hex = String::from_utf8_lossy(&bytes[i + 1..i + 3]);
+            result.push(u8::from_str_radix(&hex, 16).expect("checked hex digits!"));
+            i += 3;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(result).unwrap_or_else(|_| input.to_string())
+}
+
+/// Replace underscores by spaces and collapse whitespace runs.
+fn collapse_whitespace(input: &str) -> String {
+    input
+        .replace('_', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn capitalize_first(input: &str) -> String {
+    let mut chars = input.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Returns the canonical name of a namespace or namespace alias.
+pub fn canonical_namespace(name: &str) -> Option<&'static str> {
+    let name = collapse_whitespace(name).to_lowercase();
+    NAMESPACE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, canonical)| *canonical)
+}
+
+/// Normalize a page title to its display form:
+/// decoded, single spaces, canonical namespace and capitalized first letter.
+pub fn normalize_title(title: &str) -> String {
+    let title = collapse_whitespace(&decode_percent(title));
+    let title = title.trim_left_matches(':').trim();
+
+    if let Some(pos) = title.find(':') {
+        if let Some(namespace) = canonical_namespace(&title[..pos]) {
+            return format!(
+                "{}:{}",
+                namespace,
+                capitalize_first(title[pos + 1..].trim())
+            );
+        }
+    }
+    capitalize_first(title)
+}
+
+/// Normalize a fragment (the part after `#`) to its display form.
+pub fn normalize_fragment(fragment: &str) -> String {
+    collapse_whitespace(&decode_percent(fragment))
+}
+
+/// Encode a title for urls and anchors (underscores, urlencoded).
+pub fn encode_title(title: &str) -> String {
+    urlencode(&normalize_title(title).replace(' ', "_"))
+}
+
+/// Encode a fragment with MediaWiki's legacy dot-encoding,
+/// e.g. `Übersicht` becomes `.C3.9Cbersicht`.
+pub fn encode_fragment(fragment: &str) -> String {
+    let mut result = String::new();
+    for b in normalize_fragment(fragment).replace(' ', "_").bytes() {
+        match b as char {
+            'A'...'Z' | 'a'...'z' | '0'...'9' | '-' | '_' | '.' | ':' => result.push(b as char),
+            _ => result.push_str(&format!(".{:02X}", b)),
+        }
+    }
+    result
+}
+
+/// Split a link target into title and fragment.
+pub fn split_target(target: &str) -> (&str, Option<&str>) {
+    match target.find('#') {
+        Some(pos) => (&target[..pos], Some(&target[pos + 1..])),
+        None => (target, None),
+    }
+}
+
+/// Readable form of an anchor, like `Mathe für Nicht-Freaks: Folgen#Übersicht`.
+pub fn display_anchor(anchor: &str) -> String {
+    let (title, fragment) = split_target(anchor);
+    let mut result = normalize_title(title);
+    if let Some(fragment) = fragment {
+        result.push('#');
+        result.push_str(&normalize_fragment(&decode_dots(fragment)));
+    }
+    result
+}
+
+/// Build the anchor of a page title and an optional fragment.
+pub fn anchor(title: &str, fragment: Option<&str>) -> String {
+    let mut result = encode_title(title);
+    if let Some(fragment) = fragment {
+        if !fragment.trim().is_empty() {
+            result.push('#');
+            result.push_str(&encode_fragment(fragment));
+        }
+    }
+    result
+}
+
+/// Build the anchor of a link target. Targets without title
+/// (like `#Section`) refer to the current document.
+pub fn target_anchor(target: &str, current_title: &str) -> String {
+    let (title, fragment) = split_target(target.trim());
+    if title.trim().is_empty() {
+        anchor(current_title, fragment)
+    } else {
+        anchor(title, fragment)
+    }
+}
@@ -2,14 +2,17 @@
 
 use mediawiki_parser::*;
 // re-export common util
+use crate::anchors::AnchorIndex;
+use crate::inputs::record_input;
 use crate::meta::MediaMeta;
 use crate::settings::Settings;
+use crate::title;
 use crate::TargetType;
 pub use mwparser_utils::{
     extract_plain_text, filename_to_make, path_methods, CachedTexChecker, TexChecker,
 };
 use serde_json;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::Read;
@@ -18,15 +21,22 @@ use std::process;
 
 pub const SECTION_INCLUSION_PREFIX: &str = "#lst:";
 
-pub fn load_anchor_set(path: &str) -> io::Result<HashSet<String>> {
+/// Name of the template for extra entries of the back-of-book index,
+/// like `{{Index|Stetigkeit|sort=stetigkeit}}`. It is not part of the template spec.
+pub const INDEX_TEMPLATE: &str = "index";
+
+/// Is this template an entry of the back-of-book index?
+pub fn is_index_template(template: &Template) -> bool {
+    extract_plain_text(&template.name).trim().to_lowercase() == INDEX_TEMPLATE
+}
+
+/// Load a list of available anchors (plain or JSON index).
+pub fn load_anchor_index(path: &str) -> io::Result<AnchorIndex> {
+    record_input(path);
     let mut file = File::open(&path)?;
     let mut content = String::new();
     file.read_to_string(&mut content)?;
-    Ok(content
-        .split('\n')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect::<HashSet<String>>())
+    AnchorIndex::parse(&content)
 }
 
 /// based on <https://github.com/bt/rust_urlencoding>
@@ -46,15 +56,30 @@ pub fn urlencode(data: &str) -> String {
     escaped
 }
 
-/// encode a url for mediawiki (underscore, urlencode)
-pub fn mw_enc(input: &str) -> String {
-    urlencode(&input.trim().replace(" ", "_"))
+/// Checks if a internal reference target is available,
+/// returns the anchor if found. Targets without a title refer to `doc_title`.
+pub fn matching_anchor(target: &str, doc_title: &str, anchors: &AnchorIndex) -> Option<String> {
+    let anchor = title::target_anchor(target, doc_title);
+    if anchors.contains(&anchor) {
+        Some(anchor)
+    } else {
+        None
+    }
 }
 
-/// Checks if a internal reference target is available,
-/// returns the anchor if found.
-pub fn matching_anchor<'o>(target: &str, anchors: &'o HashSet<String>) -> Option<&'o String> {
-    anchors.get(&mw_enc(target.trim().trim_left_matches(':')))
+/// Levenshtein distance of two strings, counted in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 /// Returns a unicode character for a smiley description.
@@ -98,32 +123,223 @@ pub fn trim_prefix<'a>(input: &'a str, prefix: &str) -> &'a str {
     input
 }
 
+/// Start of a placeholder. In LaTeX, `%` starts a comment, so `<%name%>`
+/// does not collide with LaTeX source like `\def\x{{foo}}`.
+const PLACEHOLDER_START: &str = "<%";
+const PLACEHOLDER_END: &str = "%>";
+
+/// Byte range of the next `<%name%>` placeholder at or after `from`.
+/// Names consist of lowercase letters, digits and underscores.
+fn next_placeholder(input: &str, from: usize) -> Option<(usize, usize)> {
+    let mut start = from;
+    while let Some(pos) = input[start..].find(PLACEHOLDER_START) {
+        let begin = start + pos;
+        let name_start = begin + PLACEHOLDER_START.len();
+        let name_len = input[name_start..]
+            .find(|c: char| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+            .unwrap_or(input.len() - name_start);
+        let end = name_start + name_len;
+        if name_len > 0 && input[end..].starts_with(PLACEHOLDER_END) {
+            return Some((begin, end + PLACEHOLDER_END.len()));
+        }
+        start = begin + 1;
+    }
+    None
+}
+
+/// Name of the placeholder in the byte range `begin..end`.
+fn placeholder_name(input: &str, begin: usize, end: usize) -> &str {
+    &input[begin + PLACEHOLDER_START.len()..end - PLACEHOLDER_END.len()]
+}
+
+/// The placeholder for a name, like `<%name%>`.
+pub fn placeholder(name: &str) -> String {
+    format!("{}{}{}", PLACEHOLDER_START, name, PLACEHOLDER_END)
+}
+
+/// Names of all `<%name%>` placeholders in a template.
+pub fn placeholders(template: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut from = 0;
+    while let Some((begin, end)) = next_placeholder(template, from) {
+        result.push(placeholder_name(template, begin, end));
+        from = end;
+    }
+    result
+}
+
+/// Replace `<%name%>` placeholders by their values.
+/// Returns the names of placeholders without a value as error.
+pub fn fill_placeholders(
+    template: &str,
+    values: &HashMap<&str, String>,
+) -> Result<String, Vec<String>> {
+    let mut result = String::new();
+    let mut missing = vec![];
+    let mut from = 0;
+    while let Some((begin, end)) = next_placeholder(template, from) {
+        let name = placeholder_name(template, begin, end);
+        result.push_str(&template[from..begin]);
+        match values.get(name) {
+            Some(value) => result.push_str(value),
+            None => missing.push(name.to_string()),
+        }
+        from = end;
+    }
+    result.push_str(&template[from..]);
+    if missing.is_empty() {
+        Ok(result)
+    } else {
+        Err(missing)
+    }
+}
+
+/// Text between these characters is hidden markup, like the markers of the
+/// LaTeX source map. It does not count for the line width.
+pub const HIDDEN_START: char = '\u{e000}';
+pub const HIDDEN_END: char = '\u{e001}';
+
+/// Length of a string in bytes, without hidden markup.
+fn visible_len(input: &str) -> usize {
+    let mut len = 0;
+    let mut hidden = false;
+    for c in input.chars() {
+        match c {
+            HIDDEN_START => hidden = true,
+            HIDDEN_END => hidden = false,
+            _ if !hidden => len += c.len_utf8(),
+            _ => (),
+        }
+    }
+    len
+}
+
+/// Environments whose content is reproduced literally.
+const VERBATIM_ENVIRONMENTS: &[&str] = &["verbatim", "Verbatim", "lstlisting", "minted"];
+
+/// Does the line open a verbatim environment which is not closed on the same line?
+fn opens_verbatim(line: &str) -> bool {
+    VERBATIM_ENVIRONMENTS.iter().any(|env| {
+        line.contains(&format!("\\begin{{{}}}", env))
+            && !line.contains(&format!("\\end{{{}}}", env))
+    })
+}
+
+fn closes_verbatim(line: &str) -> bool {
+    VERBATIM_ENVIRONMENTS
+        .iter()
+        .any(|env| line.contains(&format!("\\end{{{}}}", env)))
+}
+
+/// Split a line of LaTeX at the spaces where it may be wrapped.
+/// Spaces inside math, braces or `\verb` are not considered.
+/// Returns the pieces and whether they are part of a `%` comment.
+fn latex_wrap_units(line: &str) -> Vec<(&str, bool)> {
+    let bytes = line.as_bytes();
+    let mut units = vec![];
+    let mut unit_start = 0;
+    let mut unit_comment = false;
+
+    let mut comment = false;
+    let mut depth = 0usize;
+    // closing delimiter of the current math mode
+    let mut math: Option<&str> = None;
+    let mut verb: Option<u8> = None;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if let Some(delimiter) = verb {
+            if c == delimiter {
+                verb = None;
+            }
+        } else if c == b' ' && (comment || (depth == 0 && math.is_none())) {
+            units.push((&line[unit_start..i], unit_comment));
+            unit_start = i + 1;
+            unit_comment = comment;
+        } else if !comment {
+            match c {
+                b'\\' => {
+                    let rest = &line[i + 1..];
+                    let is_verb = rest.starts_with("verb")
+                        && !rest[4..].starts_with(|c: char| c.is_ascii_alphabetic());
+                    if is_verb {
+                        let after = rest[4..].trim_left_matches('*');
+                        verb = after.bytes().next();
+                        i = line.len() - after.len();
+                    } else {
+                        match (rest.bytes().next(), math) {
+                            (Some(b'('), None) => math = Some("\\)"),
+                            (Some(b'['), None) => math = Some("\\]"),
+                            (Some(b')'), Some("\\)")) | (Some(b']'), Some("\\]")) => math = None,
+                            _ => (),
+                        }
+                        // skip the escaped character
+                        i += 1;
+                    }
+                }
+                b'%' => comment = true,
+                b'{' => depth += 1,
+                b'}' => depth = depth.saturating_sub(1),
+                b'$' => {
+                    let delimiter = if bytes.get(i + 1) == Some(&b'$') {
+                        i += 1;
+                        "$$"
+                    } else {
+                        "$"
+                    };
+                    match math {
+                        None => math = Some(delimiter),
+                        Some(open) if open == delimiter => math = None,
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+        }
+        i += 1;
+    }
+    units.push((&line[unit_start..], unit_comment));
+    units
+}
+
 /// Indent and trim a string.
+///
+/// Lines longer than `max_line_width` are wrapped only where this does not
+/// change the LaTeX source: never inside math, braces or verbatim content.
+/// Wrapped parts of a `%` comment stay comments.
 pub fn indent_and_trim(input: &str, depth: usize, max_line_width: usize) -> String {
     const COMMENT_PREFIX: &str = "% ";
 
     let mut lines = vec![];
+    let mut verbatim = false;
     for line in input.split('\n') {
         let trimmed = line.trim();
-        let comment = trimmed.starts_with(COMMENT_PREFIX.trim());
+        if verbatim {
+            verbatim = !closes_verbatim(trimmed);
+            lines.push(line.to_string());
+            continue;
+        }
+        verbatim = opens_verbatim(trimmed);
+
         let line_depth = depth + line.len() - line.trim_left().len();
         let start_string = format!("{:depth$}", "", depth = line_depth);
 
         let mut new_line = start_string.clone();
 
-        if trimmed.len() > max_line_width {
-            for word in trimmed.split(' ') {
-                let current_length = new_line.trim().len();
+        if visible_len(trimmed) > max_line_width {
+            for (unit, in_comment) in latex_wrap_units(trimmed) {
+                let current_length = visible_len(new_line.trim());
 
-                if current_length + word.len() + 1 > max_line_width && current_length > 0 {
+                if current_length + visible_len(unit) + 1 > max_line_width && current_length > 0 {
                     lines.push(new_line);
                     new_line = start_string.clone();
-                    if comment {
+                    if in_comment {
                         new_line.push_str(COMMENT_PREFIX);
                     }
                 }
 
-                new_line.push_str(word);
+                new_line.push_str(unit);
                 new_line.push(' ');
             }
             lines.push(new_line);
@@ -286,9 +502,8 @@ pub fn extract_content(root: Element) -> Option<Vec<Element>> {
     }
 }
 
-/// loads media meta data from the corresponding .meta file,
-/// panics on error.
-pub fn load_media_meta(name: &[Element], settings: &Settings) -> MediaMeta {
+/// Path of the .meta file belonging to a media file.
+pub fn build_media_meta_path(name: &[Element], settings: &Settings) -> PathBuf {
     let mut file_path = build_media_path(name, settings);
     let mut filename = file_path
         .file_name()
@@ -296,7 +511,14 @@ pub fn load_media_meta(name: &[Element], settings: &Settings) -> MediaMeta {
         .to_os_string();
     filename.push(".meta");
     file_path.set_file_name(filename);
+    file_path
+}
 
+/// loads media meta data from the corresponding .meta file,
+/// panics on error.
+pub fn load_media_meta(name: &[Element], settings: &Settings) -> MediaMeta {
+    let file_path = build_media_meta_path(name, settings);
+    record_input(&file_path);
     let file =
         File::open(&file_path).unwrap_or_else(|_| panic!("could not open {:?}!", &file_path));
     serde_json::from_reader(&file)
@@ -312,6 +534,10 @@ pub fn map_extension(target: TargetType, extension: &str) -> Option<String> {
         TargetType::Normalize => None,
         TargetType::Compose => None,
         TargetType::Anchors => None,
+        TargetType::Links => None,
+        TargetType::Lint => None,
+        TargetType::LinkAudit => None,
+        TargetType::Accessibility => None,
         TargetType::Latex => Some(
             match extension.trim().to_lowercase().as_str() {
                 "png" => "%.pdf",
@@ -327,6 +553,8 @@ pub fn map_extension(target: TargetType, extension: &str) -> Option<String> {
             }
             .replace("%", &extension),
         ),
+        TargetType::LatexLog => None,
+        TargetType::Bibliography => None,
         TargetType::PDF => None,
         TargetType::Stats => Some("dummy".to_string()),
         TargetType::HTML => Some(extension.to_string()),